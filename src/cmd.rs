@@ -1,8 +1,13 @@
 pub mod build;
+pub mod check;
 pub mod init;
+pub mod list;
+pub mod new;
 pub mod serve;
+pub mod theme;
 
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -15,23 +20,187 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize new site structure
-    Init {
-        /// Directory to initialize (default: current directory)
-        #[arg(default_value = ".")]
-        path: PathBuf,
+    Init(InitArgs),
+    /// Run development server with hot reload and file watching
+    Preview,
+    /// Build the static site
+    Build {
+        /// Include posts marked `draft: true`
+        #[arg(long)]
+        drafts: bool,
 
-        /// Overwrite only FerroPress scaffold files if they already exist
+        /// Include posts dated in the future
         #[arg(long)]
-        overwrite: bool,
+        future: bool,
+
+        /// Fail the build if internal links or #anchors are broken
+        #[arg(long)]
+        check_links: bool,
+
+        /// Lint rendered pages for accessibility ([a11y] in site.toml sets severities)
+        #[arg(long)]
+        a11y: bool,
+
+        /// Number of worker threads (default: one per CPU core)
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
 
-        /// Remove existing scaffold directories/files before initializing (DANGEROUS)
+        /// Write gzip and brotli variants of HTML, CSS, JS, XML and JSON files
         #[arg(long)]
-        clean: bool,
+        compress: bool,
     },
-    /// Run development server with hot reload and file watching
-    Preview,
-    /// Build the static site
-    Build,
+    /// Validate config, content and templates by rendering in memory (writes nothing)
+    Check {
+        /// Include posts marked `draft: true`
+        #[arg(long)]
+        drafts: bool,
+
+        /// Include posts dated in the future
+        #[arg(long)]
+        future: bool,
+
+        /// Also lint rendered pages for accessibility
+        #[arg(long)]
+        a11y: bool,
+
+        /// Number of worker threads (default: one per CPU core)
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
+    },
+    /// Create a new post or page from an archetype
+    New {
+        /// `post` or `page` followed by a title, or a path like posts/my-title
+        target: String,
+
+        /// Title (required with `post`/`page`; derived from the path otherwise)
+        title: Option<String>,
+    },
+    /// List posts with their status and output path
+    List(ListArgs),
     /// Serve the production build
-    Serve,
+    Serve {
+        /// Serve the existing dist/ as-is instead of rebuilding it first
+        #[arg(long)]
+        no_build: bool,
+
+        /// Serve this directory as-is instead of the project's dist/ (implies --no-build)
+        #[arg(long, value_name = "PATH")]
+        dir: Option<PathBuf>,
+    },
+    /// Install, inspect and package themes
+    Theme {
+        #[command(subcommand)]
+        command: ThemeCommand,
+    },
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Directory to initialize (default: current directory)
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+
+    /// Overwrite only FerroPress scaffold files if they already exist
+    #[arg(long)]
+    pub overwrite: bool,
+
+    /// Remove existing scaffold directories/files before initializing (DANGEROUS)
+    #[arg(long)]
+    pub clean: bool,
+
+    /// Starter kit to scaffold (default: blog)
+    #[arg(long, value_enum)]
+    pub starter: Option<init::Starter>,
+
+    /// Scaffold from a local template directory instead ({{ title }}-style placeholders are filled in, escaped for TOML and YAML)
+    #[arg(long, conflicts_with = "starter")]
+    pub from: Option<PathBuf>,
+
+    /// Site title for site.toml
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Site author for site.toml
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// Public base URL for site.toml, e.g. https://example.com/
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// Site language for site.toml, e.g. en or fr-CA
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Never prompt; use defaults for anything not given as a flag
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// Only posts with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Only posts dated on or after this day (YYYY-MM-DD)
+    #[arg(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Only posts dated on or before this day (YYYY-MM-DD)
+    #[arg(long)]
+    pub until: Option<NaiveDate>,
+
+    /// Include posts marked `draft: true`
+    #[arg(long)]
+    pub drafts: bool,
+
+    /// Include posts dated in the future
+    #[arg(long)]
+    pub future: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    pub format: list::ListFormat,
+}
+
+#[derive(Subcommand)]
+pub enum ThemeCommand {
+    /// Install a theme from a local directory, git repository or packaged archive
+    Add {
+        /// Local path, git URL or .tar.gz produced by `theme package`
+        source: String,
+
+        /// Name to install under themes/ (default: the theme's own name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Replace the theme if it is already installed
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// List installed themes
+    List,
+    /// Copy a theme file into the project so it can be overridden
+    Eject {
+        /// File or directory inside the theme, e.g. templates/post.html or templates/partials
+        file: PathBuf,
+
+        /// Theme to eject from (default: the active theme in site.toml)
+        #[arg(long)]
+        theme: Option<String>,
+
+        /// Overwrite project files that already exist
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Package a theme as a distributable .tar.gz with a manifest
+    Package {
+        /// Theme to package (default: the active theme in site.toml)
+        name: Option<String>,
+
+        /// Output archive path (default: <name>-<version>.tar.gz)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
 }
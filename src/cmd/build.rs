@@ -1,25 +1,54 @@
-use crate::engine;
-use crate::models::{ProjectPaths, SiteConfig};
+use crate::engine::{self, a11y, links, utils};
+use crate::models::{BuildOptions, ProjectPaths, Severity};
 use anyhow::{Context, Result};
 
-pub async fn execute() -> Result<()> {
+pub async fn execute(options: BuildOptions) -> Result<()> {
     let start = std::time::Instant::now();
+    engine::configure_jobs(options.jobs)?;
 
     let paths = ProjectPaths::default();
-    let config = SiteConfig::load(&paths.config).context("Failed to load site.toml")?;
+    let config = engine::load_config(&paths)?;
 
     println!("📦 THE CASTING: Preparing Production Build...");
 
-    // start with clean slate and proper structure
-    paths.clean_dist().context("Failed to clean dist/")?;
     paths
         .create_dist_folders()
         .context("Failed to create dist/")?;
 
-    // build and copy assets then render pages
-    engine::build_css(&paths).context("Tailwind build failed")?;
-    engine::copy_static_assets(&paths).context("Copying static assets failed")?;
-    engine::render_site(&paths, &config).context("Rendering site failed")?;
+    // build and copy assets then render pages; unchanged files are left alone
+    engine::build_css(&paths, &config).context("Tailwind build failed")?;
+    let pages = engine::build_site(&paths, &config, &options)?;
+
+    if options.check_links {
+        println!("🔗 Checking internal links...");
+        let files = utils::relative_files(&paths.dist)?.into_keys().collect();
+        let broken = links::check_links(&pages, &files, &config);
+        for link in &broken {
+            eprintln!("❌ Broken link in {}", link);
+        }
+        if !broken.is_empty() {
+            anyhow::bail!("{} broken internal link(s)", broken.len());
+        }
+    }
+
+    if options.a11y {
+        println!("♿ Linting accessibility...");
+        let findings = a11y::lint_pages(&pages, &config.a11y);
+        for finding in &findings {
+            match finding.severity {
+                Severity::Error => eprintln!("❌ {}", finding),
+                _ => eprintln!("⚠️  {}", finding),
+            }
+        }
+
+        let errors = findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .count();
+        if errors > 0 {
+            anyhow::bail!("{} accessibility error(s)", errors);
+        }
+    }
 
     println!("🏆 CASTING COMPLETE in {:?}.", start.elapsed());
     Ok(())
@@ -0,0 +1,36 @@
+use crate::engine::{self, check};
+use crate::models::{BuildOptions, ProjectPaths};
+use anyhow::Result;
+
+/// Run the full pipeline in memory and report every problem found.
+/// Nothing is written to dist/.
+pub async fn execute(options: BuildOptions) -> Result<()> {
+    let start = std::time::Instant::now();
+    engine::configure_jobs(options.jobs)?;
+    let paths = ProjectPaths::default();
+
+    println!("🔍 INSPECTION: Checking site without casting...");
+    let report = check::check_site(&paths, &options);
+
+    for warning in &report.warnings {
+        eprintln!("⚠️  {}", warning);
+    }
+    for error in &report.errors {
+        eprintln!("❌ {:#}", error);
+    }
+
+    if !report.errors.is_empty() {
+        anyhow::bail!(
+            "{} problem(s) found ({} page(s) rendered cleanly)",
+            report.errors.len(),
+            report.pages.len()
+        );
+    }
+
+    println!(
+        "✅ All {} page(s) rendered without errors in {:?}.",
+        report.pages.len(),
+        start.elapsed()
+    );
+    Ok(())
+}
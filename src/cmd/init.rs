@@ -1,25 +1,64 @@
+mod starters;
+
+pub use starters::Starter;
+
+use crate::cmd::InitArgs;
+use crate::engine::{theme, utils};
 use crate::models::{ProjectPaths, SiteConfig};
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use std::{
     fs,
+    io::{self, BufRead, IsTerminal, Write},
     path::{Path, PathBuf},
     process::Command,
 };
 
-pub const DEFAULT_LAYOUT: &str = include_str!("../../defaults/templates/layouts/base.html");
-pub const DEFAULT_INDEX: &str = include_str!("../../defaults/templates/pages/index.html");
-pub const DEFAULT_404: &str = include_str!("../../defaults/templates/pages/404.html");
-pub const DEFAULT_POST_TEMPLATE: &str = include_str!("../../defaults/templates/post.html");
-pub const DEFAULT_CSS: &str = include_str!("../../defaults/static/css/input.css");
-pub const DEFAULT_HTMX: &str = include_str!("../../defaults/static/js/htmx.min.js");
-pub const EXAMPLE_POST_1: &str = include_str!("../../defaults/content/posts/hello.md");
-pub const EXAMPLE_POST_2: &str = include_str!("../../defaults/content/posts/2025/hello.md");
+/// A file written by `init`, relative to the project root.
+struct Blueprint {
+    path: PathBuf,
+    contents: Vec<u8>,
+}
+
+/// Site details asked for by `init`, also substituted for `{{ name }}` placeholders.
+struct ScaffoldVars {
+    title: String,
+    author: String,
+    base_url: String,
+    language: String,
+}
+
+impl ScaffoldVars {
+    fn pairs(&self) -> [(&'static str, &str); 4] {
+        [
+            ("title", &self.title),
+            ("author", &self.author),
+            ("base_url", &self.base_url),
+            ("language", &self.language),
+        ]
+    }
+}
 
-/// Initialize a FerroPress site at `path`.
+/// Initialize a FerroPress site at `args.path`.
 /// - `overwrite`: overwrite only the scaffold files FerroPress manages
 /// - `clean`: remove existing scaffold directories/files before initializing (dangerous)
-pub async fn execute(path: PathBuf, overwrite: bool, clean: bool) -> Result<()> {
-    let root = path;
+/// - `starter`: embedded starter kit to scaffold from
+/// - `from`: local template directory to scaffold from instead of a starter
+///
+/// On a terminal, anything not given as a flag is prompted for unless `--yes` is set.
+pub async fn execute(args: InitArgs) -> Result<()> {
+    let interactive = !args.yes && io::stdin().is_terminal();
+    let (vars, starter) = gather_answers(&args, interactive)?;
+
+    let root = args.path;
+    let (overwrite, clean, from) = (args.overwrite, args.clean, args.from);
+
+    // define blueprints before touching the filesystem so a bad --from fails early
+    let mut blueprints = match &from {
+        Some(dir) => template_dir_blueprints(dir, &vars)?,
+        None => starter_blueprints(starter, &vars)?,
+    };
+    apply_site_answers(&mut blueprints, &vars)?;
 
     // create root if needed
     if !root.exists() {
@@ -30,52 +69,29 @@ pub async fn execute(path: PathBuf, overwrite: bool, clean: bool) -> Result<()>
     let paths = ProjectPaths::from_root(&root);
 
     if clean {
-        clean_scaffold(&paths)?;
+        clean_scaffold(&root, &paths, &blueprints)?;
     } else if !overwrite {
         // Safe default: refuse if any of our managed scaffold files already exist
         // (prevents accidental clobber in an existing repo)
-        ensure_no_scaffold_conflicts(&paths)?;
+        ensure_no_scaffold_conflicts(&root, &blueprints)?;
     }
 
-    println!(
-        "⚒️  FERROPRESS: Drafting the Blueprints in {}...",
-        root.display()
-    );
-
-    // Build site.toml from default config
-    let default_config = SiteConfig::default();
-    let config_str = toml::to_string_pretty(&default_config)
-        .context("Failed to serialize default SiteConfig to TOML")?;
-
-    // define blueprints (content, desination)
-    let blueprints: Vec<(&str, PathBuf)> = vec![
-        (&config_str, paths.config.clone()),
-        (
-            DEFAULT_LAYOUT,
-            paths.templates.join("layouts").join("base.html"),
-        ),
-        (
-            DEFAULT_INDEX,
-            paths.templates.join("pages").join("index.html"),
-        ),
-        (DEFAULT_404, paths.templates.join("pages").join("404.html")),
-        (DEFAULT_POST_TEMPLATE, paths.templates.join("post.html")),
-        (
-            DEFAULT_CSS,
-            paths.static_files.join("css").join("input.css"),
-        ),
-        (
-            DEFAULT_HTMX,
-            paths.static_files.join("js").join("htmx.min.js"),
+    match &from {
+        Some(dir) => println!(
+            "⚒️  FERROPRESS: Drafting the Blueprints from {} in {}...",
+            dir.display(),
+            root.display()
         ),
-        (EXAMPLE_POST_1, paths.content.join("posts").join("hello.md")),
-        (
-            EXAMPLE_POST_2,
-            paths.content.join("posts").join("2025").join("hello.md"),
+        None => println!(
+            "⚒️  FERROPRESS: Drafting the {:?} Blueprints in {}...",
+            starter,
+            root.display()
         ),
-    ];
+    }
+
+    for blueprint in blueprints {
+        let dest = root.join(&blueprint.path);
 
-    for (content, dest) in blueprints {
         // Shouldn't happen because ensure_no_scaffold_conflicts() catches it,
         // but keep this as a safety net.
         if dest.exists() && !overwrite && !clean {
@@ -90,7 +106,7 @@ pub async fn execute(path: PathBuf, overwrite: bool, clean: bool) -> Result<()>
                 .with_context(|| format!("Failed to create directory {}", parent.display()))?;
         }
 
-        fs::write(&dest, content)
+        fs::write(&dest, &blueprint.contents)
             .with_context(|| format!("Failed to write file {}", dest.display()))?;
     }
 
@@ -119,16 +135,24 @@ pub async fn execute(path: PathBuf, overwrite: bool, clean: bool) -> Result<()>
 }
 
 /// Refuse to overwrite if any managed scaffold files already exist.
-fn ensure_no_scaffold_conflicts(paths: &ProjectPaths) -> Result<()> {
-    let managed = managed_scaffold_files(paths);
-    let conflicts: Vec<PathBuf> = managed.into_iter().filter(|p| p.exists()).collect();
+fn ensure_no_scaffold_conflicts(root: &Path, blueprints: &[Blueprint]) -> Result<()> {
+    ensure_no_conflicts(
+        "Scaffold already exists",
+        managed_scaffold_files(root, blueprints.iter().map(|b| &b.path)),
+        "Re-run with --overwrite to overwrite scaffold files, or --clean to remove scaffold first.",
+    )
+}
+
+/// Refuse if any of `files` already exist, listing every conflict at once.
+pub(crate) fn ensure_no_conflicts(heading: &str, files: Vec<PathBuf>, hint: &str) -> Result<()> {
+    let conflicts: Vec<PathBuf> = files.into_iter().filter(|p| p.exists()).collect();
 
     if !conflicts.is_empty() {
-        let mut msg = String::from("Scaffold already exists:\n");
+        let mut msg = format!("{}:\n", heading);
         for c in conflicts {
             msg.push_str(&format!("  - {}\n", c.display()));
         }
-        msg.push_str("Re-run with --overwrite to overwrite scaffold files, or --clean to remove scaffold first.");
+        msg.push_str(hint);
         anyhow::bail!(msg);
     }
 
@@ -136,7 +160,8 @@ fn ensure_no_scaffold_conflicts(paths: &ProjectPaths) -> Result<()> {
 }
 
 /// Remove known scaffold directories/files (dangerous but bounded).
-fn clean_scaffold(paths: &ProjectPaths) -> Result<()> {
+/// A directory is only removed when the new scaffold writes into it.
+fn clean_scaffold(root: &Path, paths: &ProjectPaths, blueprints: &[Blueprint]) -> Result<()> {
     // Prefer boudned deletion: remove only known top-level scaffold dirs/fils.
     // (Safer than nuking the entire root directory.)
     let to_remove_files = [paths.config.clone()];
@@ -151,9 +176,13 @@ fn clean_scaffold(paths: &ProjectPaths) -> Result<()> {
         paths.content.clone(),
         paths.templates.clone(),
         paths.static_files.clone(),
+        paths.data.clone(),
     ];
     for d in to_remove_dirs {
-        if d.exists() {
+        let written = blueprints
+            .iter()
+            .any(|b| root.join(&b.path).starts_with(&d));
+        if written && d.exists() {
             fs::remove_dir_all(&d)
                 .with_context(|| format!("Failed to remove directory {}", d.display()))?;
         }
@@ -162,18 +191,275 @@ fn clean_scaffold(paths: &ProjectPaths) -> Result<()> {
     Ok(())
 }
 
-fn managed_scaffold_files(paths: &ProjectPaths) -> Vec<PathBuf> {
-    vec![
-        paths.config.clone(),
-        paths.templates.join("layouts").join("base.html"),
-        paths.templates.join("pages").join("index.html"),
-        paths.templates.join("pages").join("404.html"),
-        paths.templates.join("post.html"),
-        paths.static_files.join("css").join("input.css"),
-        paths.static_files.join("js").join("htmx.min.js"),
-        paths.content.join("posts").join("hello.md"),
-        paths.content.join("posts").join("2025").join("hello.md"),
-    ]
+/// Resolve site details from flags, prompting for the rest when interactive.
+fn gather_answers(args: &InitArgs, interactive: bool) -> Result<(ScaffoldVars, Starter)> {
+    let defaults = SiteConfig::default();
+    let ask = |flag: &Option<String>, label: &str, default: &str| -> Result<String> {
+        match flag {
+            Some(value) => Ok(value.clone()),
+            None if interactive => prompt(label, default),
+            None => Ok(default.to_string()),
+        }
+    };
+
+    let vars = ScaffoldVars {
+        title: ask(&args.title, "Site title", &defaults.title)?,
+        author: ask(&args.author, "Author", &defaults.author)?,
+        base_url: ask(&args.base_url, "Base URL", &defaults.base_url)?,
+        language: ask(&args.language, "Language", &defaults.language)?,
+    };
+
+    let starter = match args.starter {
+        Some(starter) => starter,
+        None if interactive && args.from.is_none() => loop {
+            let names: Vec<String> = Starter::value_variants()
+                .iter()
+                .filter_map(|s| s.to_possible_value().map(|v| v.get_name().to_string()))
+                .collect();
+            let answer = prompt(&format!("Starter ({})", names.join(", ")), "blog")?;
+            match Starter::from_str(&answer, true) {
+                Ok(starter) => break starter,
+                Err(_) => println!("Unknown starter '{}'.", answer),
+            }
+        },
+        None => Starter::default(),
+    };
+
+    Ok((vars, starter))
+}
+
+/// Ask on stdout, read a line from stdin; an empty answer keeps the default.
+fn prompt(label: &str, default: &str) -> Result<String> {
+    print!("{} [{}]: ", label, default);
+    io::stdout().flush().context("Failed to write prompt")?;
+
+    let mut line = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("Failed to read answer")?;
+
+    let answer = line.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+/// Write the init answers into the site.toml blueprint, whatever the starter shipped.
+/// Edited in place so the values are escaped and comments and unknown keys survive.
+fn apply_site_answers(blueprints: &mut [Blueprint], vars: &ScaffoldVars) -> Result<()> {
+    let Some(site) = blueprints
+        .iter_mut()
+        .find(|b| b.path == Path::new("site.toml"))
+    else {
+        return Ok(());
+    };
+
+    let text = String::from_utf8(site.contents.clone()).context("site.toml is not valid UTF-8")?;
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .context("Failed to parse the scaffolded site.toml")?;
+
+    for (key, answer) in [
+        ("title", &vars.title),
+        ("author", &vars.author),
+        ("base_url", &vars.base_url),
+        ("language", &vars.language),
+    ] {
+        match doc.get_mut(key).and_then(|item| item.as_value_mut()) {
+            Some(value) => {
+                let decor = value.decor().clone();
+                *value = answer.as_str().into();
+                *value.decor_mut() = decor;
+            }
+            None => doc[key] = toml_edit::value(answer.as_str()),
+        }
+    }
+
+    let text = doc.to_string();
+    toml::from_str::<SiteConfig>(&text)
+        .context("The scaffolded site.toml is not a valid config")?;
+    site.contents = text.into_bytes();
+    Ok(())
+}
+
+/// Where each of `files` (relative to `root`) gets written, for conflict checks.
+/// Shared with `theme eject`, which scaffolds theme files into the project.
+pub(crate) fn managed_scaffold_files<P: AsRef<Path>>(
+    root: &Path,
+    files: impl IntoIterator<Item = P>,
+) -> Vec<PathBuf> {
+    files.into_iter().map(|file| root.join(file)).collect()
+}
+
+fn starter_blueprints(starter: Starter, vars: &ScaffoldVars) -> Result<Vec<Blueprint>> {
+    starter
+        .files()
+        .iter()
+        .map(|file| {
+            Ok(Blueprint {
+                path: PathBuf::from(file.path),
+                contents: render_placeholders(file.path, file.content.as_bytes(), vars)?,
+            })
+        })
+        .collect()
+}
+
+/// Every file of a local template directory, with a generated site.toml if it lacks one.
+fn template_dir_blueprints(dir: &Path, vars: &ScaffoldVars) -> Result<Vec<Blueprint>> {
+    if !dir.is_dir() {
+        anyhow::bail!("Template directory {} does not exist", dir.display());
+    }
+
+    let mut blueprints = Vec::new();
+    for (relative, path) in theme::layered_files(&[dir.to_path_buf()])? {
+        if relative.starts_with(".git/") {
+            continue;
+        }
+        let raw = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        blueprints.push(Blueprint {
+            contents: render_placeholders(&relative, &raw, vars)?,
+            path: PathBuf::from(relative),
+        });
+    }
+
+    if !blueprints.iter().any(|b| b.path == Path::new("site.toml")) {
+        // apply_site_answers() fills in the details
+        let config_str = toml::to_string_pretty(&SiteConfig::default())
+            .context("Failed to serialize default SiteConfig to TOML")?;
+        blueprints.push(Blueprint {
+            path: PathBuf::from("site.toml"),
+            contents: config_str.into_bytes(),
+        });
+    }
+
+    Ok(blueprints)
+}
+
+/// Substitute `{{ title }}`-style placeholders in text files.
+/// Templates are left alone because `{{ ... }}` is Tera syntax there, and
+/// site.toml because apply_site_answers() sets its values with proper escaping.
+/// In YAML (data files and Markdown front matter) and TOML the values are
+/// escaped for wherever the placeholder sits, so any title keeps them valid.
+fn render_placeholders(relative: &str, contents: &[u8], vars: &ScaffoldVars) -> Result<Vec<u8>> {
+    if relative.starts_with("templates/") || relative == "site.toml" {
+        return Ok(contents.to_vec());
+    }
+    let Ok(text) = std::str::from_utf8(contents) else {
+        return Ok(contents.to_vec());
+    };
+
+    let extension = Path::new(relative)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let filled = match extension {
+        "yaml" | "yml" => fill_escaped(text, vars, Syntax::Yaml),
+        "toml" => fill_escaped(text, vars, Syntax::Toml),
+        "md" => match split_front_matter(text) {
+            Some((front_matter, body)) => fill_escaped(front_matter, vars, Syntax::Yaml)
+                .map(|front_matter| front_matter + &utils::fill_placeholders(body, &vars.pairs())),
+            None => Ok(utils::fill_placeholders(text, &vars.pairs())),
+        },
+        _ => Ok(utils::fill_placeholders(text, &vars.pairs())),
+    };
+
+    filled
+        .map(String::into_bytes)
+        .with_context(|| format!("Failed to fill in placeholders in {}", relative))
+}
+
+/// The language a placeholder is filled into.
+#[derive(Clone, Copy)]
+enum Syntax {
+    Yaml,
+    Toml,
+}
+
+/// `---` front matter (delimiters included) and the rest of a Markdown file.
+fn split_front_matter(text: &str) -> Option<(&str, &str)> {
+    if !text.starts_with("---") {
+        return None;
+    }
+    let mut offset = 0;
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        offset += line.len();
+        if i > 0 && line.trim_end() == "---" {
+            return Some(text.split_at(offset));
+        }
+    }
+    None
+}
+
+/// Fill in placeholders, escaping each value for the quotes around it: string
+/// contents inside `"..."` or `'...'`, a whole scalar when unquoted.
+fn fill_escaped(text: &str, vars: &ScaffoldVars, syntax: Syntax) -> Result<String> {
+    let placeholders: Vec<(String, &str)> = vars
+        .pairs()
+        .into_iter()
+        .flat_map(|(name, value)| {
+            [
+                (format!("{{{{ {} }}}}", name), value),
+                (format!("{{{{{}}}}}", name), value),
+            ]
+        })
+        .collect();
+
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let mut quote: Option<char> = None;
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            if let Some((placeholder, value)) = placeholders
+                .iter()
+                .find(|(placeholder, _)| rest.starts_with(placeholder.as_str()))
+            {
+                out.push_str(&escape(value, quote, syntax)?);
+                rest = &rest[placeholder.len()..];
+                continue;
+            }
+
+            match (quote, c) {
+                (Some('"'), '\\') => {
+                    // keep the escaped character, whatever it is
+                    let escaped = rest.chars().nth(1).map_or(0, char::len_utf8);
+                    out.push_str(&rest[..1 + escaped]);
+                    rest = &rest[1 + escaped..];
+                    continue;
+                }
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(open), _) if c == open => quote = None,
+                _ => {}
+            }
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Ok(out)
+}
+
+fn escape(value: &str, quote: Option<char>, syntax: Syntax) -> Result<String> {
+    Ok(match (quote, syntax) {
+        // JSON string escapes are valid in both YAML and TOML double quotes
+        (Some('"'), _) => {
+            let json = serde_json::to_string(value)?;
+            json[1..json.len() - 1].to_string()
+        }
+        (Some(_), Syntax::Yaml) => value.replace('\'', "''"),
+        (Some(_), Syntax::Toml) => {
+            if value.contains(['\'', '\n']) {
+                anyhow::bail!(
+                    "{:?} can't go in a single-quoted TOML string; use double quotes",
+                    value
+                );
+            }
+            value.to_string()
+        }
+        (None, Syntax::Yaml) => serde_yaml::to_string(value)?.trim_end().to_string(),
+        (None, Syntax::Toml) => toml_edit::Value::from(value).to_string(),
+    })
 }
 
 fn init_git(root: &Path) -> Result<bool> {
@@ -193,6 +479,7 @@ fn init_git(root: &Path) -> Result<bool> {
         Ok(status) if status.success() => {
             let output = Command::new("git")
                 .args(["init", "-b", "main"])
+                .current_dir(root)
                 .output()
                 .context("Failed to execute git init")?;
 
@@ -204,24 +491,208 @@ fn init_git(root: &Path) -> Result<bool> {
 
 fn upsert_gitignore(root: &Path) -> Result<()> {
     let path = root.join(".gitignore");
-    let block = "/dist\n/target\n.DS_Store\n";
+    let entries = ["/dist", "/.ferropress-cache", "/target", ".DS_Store"];
 
     if path.exists() {
         let existing = fs::read_to_string(&path)
             .with_context(|| format!("Failed to read {}", path.display()))?;
-        if !existing.contains("/dist") {
+        let missing: Vec<&str> = entries
+            .iter()
+            .copied()
+            .filter(|entry| !existing.lines().any(|line| line.trim() == *entry))
+            .collect();
+        if !missing.is_empty() {
             let mut new_contents = existing;
-            if !new_contents.ends_with('\n') {
+            if !new_contents.is_empty() && !new_contents.ends_with('\n') {
+                new_contents.push('\n');
+            }
+            for entry in missing {
+                new_contents.push_str(entry);
                 new_contents.push('\n');
             }
-            new_contents.push_str(block);
             fs::write(&path, new_contents)
                 .with_context(|| format!("Failed to write {}", path.display()))?;
-        } else {
-            fs::write(&path, block)
-                .with_context(|| format!("Failed to write {}", path.display()))?;
         }
+    } else {
+        fs::write(&path, entries.join("\n") + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{self, check, data, parser};
+    use crate::models::BuildOptions;
+    use tempfile::tempdir;
+
+    fn vars() -> ScaffoldVars {
+        ScaffoldVars {
+            title: r#"Ann "Bo": #1 site's \o/"#.to_string(),
+            author: "Ann: the 'first'".to_string(),
+            base_url: "/".to_string(),
+            language: "en".to_string(),
+        }
+    }
+
+    fn scaffold(root: &Path, blueprints: Vec<Blueprint>) {
+        for blueprint in blueprints {
+            let dest = root.join(&blueprint.path);
+            fs::create_dir_all(dest.parent().unwrap()).unwrap();
+            fs::write(dest, blueprint.contents).unwrap();
+        }
+    }
+
+    /// Check the scaffolded site and build it, returning the config it built with.
+    fn assert_builds(root: &Path) -> SiteConfig {
+        let paths = ProjectPaths::from_root(root);
+        let report = check::check_site(&paths, &BuildOptions::default());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+        // stands in for Tailwind, which runs before build_site()
+        let css = paths.output_css_file();
+        fs::create_dir_all(css.parent().unwrap()).unwrap();
+        fs::write(css, "").unwrap();
+        let config = engine::load_config(&paths).unwrap();
+        engine::build_site(&paths, &config, &BuildOptions::default()).unwrap();
+        assert!(paths.dist.join("index.html").is_file());
+        config
+    }
+
+    #[test]
+    fn test_site_answers_are_escaped_and_keep_the_rest_of_the_file() {
+        let vars = ScaffoldVars {
+            title: r#"Ann "Bo" Site \o/"#.to_string(),
+            author: "Ann".to_string(),
+            base_url: "/".to_string(),
+            language: "en".to_string(),
+        };
+        let site = "# my site\ntitle = \"{{ title }}\" # shown in the header\nmascot = \"crab\"\nfooter_text = \"\"\n";
+        let mut blueprints = vec![Blueprint {
+            path: PathBuf::from("site.toml"),
+            contents: render_placeholders("site.toml", site.as_bytes(), &vars).unwrap(),
+        }];
+
+        apply_site_answers(&mut blueprints, &vars).unwrap();
+
+        let text = String::from_utf8(blueprints.remove(0).contents).unwrap();
+        let config: SiteConfig = toml::from_str(&text).unwrap();
+        assert_eq!(config.title, vars.title);
+        assert_eq!(config.author, "Ann");
+        assert!(text.starts_with("# my site\n"), "{text}");
+        assert!(text.contains("# shown in the header"), "{text}");
+        assert!(text.contains("mascot = \"crab\""), "{text}");
+    }
+
+    #[test]
+    fn test_every_starter_scaffolds_a_site_that_builds() {
+        let vars = vars();
+        for &starter in Starter::value_variants() {
+            let dir = tempdir().unwrap();
+            let mut blueprints = starter_blueprints(starter, &vars).unwrap();
+            apply_site_answers(&mut blueprints, &vars).unwrap();
+
+            assert_eq!(blueprints.len(), starter.files().len());
+            for (blueprint, file) in blueprints.iter().zip(starter.files()) {
+                if file.path.starts_with("templates/") {
+                    assert_eq!(blueprint.contents, file.content.as_bytes(), "{}", file.path);
+                }
+            }
+            scaffold(dir.path(), blueprints);
+
+            let config = assert_builds(dir.path());
+            assert_eq!(config.title, vars.title, "{:?}", starter);
+            assert_eq!(config.author, vars.author, "{:?}", starter);
+        }
+    }
+
+    #[test]
+    fn test_from_escapes_placeholders_for_each_file_type() {
+        let (template, dir) = (tempdir().unwrap(), tempdir().unwrap());
+        let write = |relative: &str, contents: &str| {
+            let path = template.path().join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        };
+        let page = "<html lang=\"en\">{{ site.title }} {% for post in posts %}{{ post.metadata.title }}{% endfor %}</html>";
+        write("templates/pages/index.html", page);
+        write(
+            "templates/post.html",
+            "<html lang=\"en\">{{ content | safe }}</html>",
+        );
+        write(
+            "content/posts/hello.md",
+            "---\ntitle: {{ title }}\ndescription: 'By {{ author }}'\ndate: 2025-01-01\ntags: [\"{{title}}\"]\n---\n# {{ title }}\n",
+        );
+        write(
+            "data/team.yaml",
+            "- name: {{ author }}\n  site: \"{{ title }}\"\n",
+        );
+        write(
+            "extra.toml",
+            "name = \"{{ title }}\"\nauthor = {{ author }}\n",
+        );
+        write(".git/HEAD", "ref: refs/heads/main\n");
+        let vars = vars();
+
+        let mut blueprints = template_dir_blueprints(template.path(), &vars).unwrap();
+        apply_site_answers(&mut blueprints, &vars).unwrap();
+        scaffold(dir.path(), blueprints);
+
+        let root = dir.path();
+        assert!(!root.join(".git").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("templates/pages/index.html")).unwrap(),
+            page
+        );
+        let post = parser::parse_post(&root.join("content/posts/hello.md"), root).unwrap();
+        assert_eq!(post.metadata.title, vars.title);
+        assert_eq!(post.metadata.tags, std::slice::from_ref(&vars.title));
+        assert_eq!(
+            post.metadata.description.as_deref(),
+            Some(format!("By {}", vars.author).as_str())
+        );
+        assert_eq!(post.content.trim(), format!("# {}", vars.title));
+        let team = data::load_data_dir(&root.join("data")).unwrap();
+        assert_eq!(team["team"][0]["name"], vars.author.as_str());
+        assert_eq!(team["team"][0]["site"], vars.title.as_str());
+        let extra: toml::Table =
+            toml::from_str(&fs::read_to_string(root.join("extra.toml")).unwrap()).unwrap();
+        assert_eq!(extra["name"].as_str(), Some(vars.title.as_str()));
+        assert_eq!(extra["author"].as_str(), Some(vars.author.as_str()));
+
+        let config = assert_builds(root);
+        assert_eq!(config.title, vars.title);
+    }
+
+    #[test]
+    fn test_from_rejects_a_value_a_literal_toml_string_cannot_hold() {
+        let err =
+            render_placeholders("extra.toml", b"name = '{{ author }}'\n", &vars()).unwrap_err();
+        assert!(format!("{:#}", err).contains("extra.toml"), "{err:#}");
+    }
+
+    #[test]
+    fn test_clean_only_removes_directories_the_scaffold_writes() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        fs::create_dir_all(&paths.data).unwrap();
+        fs::write(paths.data.join("mine.json"), "[]").unwrap();
+        fs::create_dir_all(&paths.content).unwrap();
+        fs::write(paths.content.join("old.md"), "").unwrap();
+        fs::write(&paths.config, "").unwrap();
+
+        let blueprints = starter_blueprints(Starter::Blog, &vars()).unwrap();
+        clean_scaffold(dir.path(), &paths, &blueprints).unwrap();
+
+        assert!(paths.data.join("mine.json").is_file());
+        assert!(!paths.content.exists());
+        assert!(!paths.config.exists());
+
+        let blueprints = starter_blueprints(Starter::Portfolio, &vars()).unwrap();
+        clean_scaffold(dir.path(), &paths, &blueprints).unwrap();
+        assert!(!paths.data.exists());
+    }
+}
@@ -0,0 +1,99 @@
+use clap::ValueEnum;
+
+/// A file shipped inside the binary, relative to the project root.
+pub struct StarterFile {
+    pub path: &'static str,
+    pub content: &'static str,
+}
+
+/// Embed `defaults/starters/<starter>/<path>` as `<path>`.
+macro_rules! starter_file {
+    ($starter:literal, $path:literal) => {
+        StarterFile {
+            path: $path,
+            content: include_str!(concat!("../../../defaults/starters/", $starter, "/", $path)),
+        }
+    };
+}
+
+/// Embed a file from `defaults/` shared by every starter.
+macro_rules! shared_file {
+    ($path:literal) => {
+        StarterFile {
+            path: $path,
+            content: include_str!(concat!("../../../defaults/", $path)),
+        }
+    };
+}
+
+const BLOG: &[StarterFile] = &[
+    starter_file!("blog", "site.toml"),
+    starter_file!("blog", "templates/layouts/base.html"),
+    starter_file!("blog", "templates/pages/index.html"),
+    starter_file!("blog", "templates/pages/404.html"),
+    starter_file!("blog", "templates/post.html"),
+    shared_file!("static/css/input.css"),
+    shared_file!("static/js/htmx.min.js"),
+    starter_file!("blog", "content/posts/hello.md"),
+    starter_file!("blog", "content/posts/2025/hello.md"),
+];
+
+const DOCS: &[StarterFile] = &[
+    starter_file!("docs", "site.toml"),
+    starter_file!("docs", "templates/layouts/base.html"),
+    starter_file!("docs", "templates/pages/index.html"),
+    starter_file!("docs", "templates/pages/404.html"),
+    starter_file!("docs", "templates/post.html"),
+    shared_file!("static/css/input.css"),
+    shared_file!("static/js/htmx.min.js"),
+    starter_file!("docs", "content/posts/getting-started.md"),
+    starter_file!("docs", "content/posts/guides/configuration.md"),
+];
+
+const PORTFOLIO: &[StarterFile] = &[
+    starter_file!("portfolio", "site.toml"),
+    starter_file!("portfolio", "templates/layouts/base.html"),
+    starter_file!("portfolio", "templates/pages/index.html"),
+    starter_file!("portfolio", "templates/pages/404.html"),
+    starter_file!("portfolio", "templates/post.html"),
+    starter_file!("portfolio", "templates/project.html"),
+    shared_file!("static/css/input.css"),
+    shared_file!("static/js/htmx.min.js"),
+    starter_file!("portfolio", "data/projects.yaml"),
+    starter_file!("portfolio", "content/posts/hello.md"),
+];
+
+const MINIMAL: &[StarterFile] = &[
+    starter_file!("minimal", "site.toml"),
+    starter_file!("minimal", "templates/layouts/base.html"),
+    starter_file!("minimal", "templates/pages/index.html"),
+    starter_file!("minimal", "templates/pages/404.html"),
+    starter_file!("minimal", "templates/post.html"),
+    shared_file!("static/css/input.css"),
+    starter_file!("minimal", "content/posts/hello.md"),
+];
+
+/// Starter kits embedded in the binary for `ferropress init --starter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Starter {
+    /// Journal with a post grid (the classic FerroPress look)
+    #[default]
+    Blog,
+    /// Documentation with a section sidebar
+    Docs,
+    /// Project showcase generated from data/projects.yaml
+    Portfolio,
+    /// Bare-bones layout to build on
+    Minimal,
+}
+
+impl Starter {
+    pub fn files(self) -> &'static [StarterFile] {
+        match self {
+            Starter::Blog => BLOG,
+            Starter::Docs => DOCS,
+            Starter::Portfolio => PORTFOLIO,
+            Starter::Minimal => MINIMAL,
+        }
+    }
+}
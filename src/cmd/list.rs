@@ -0,0 +1,327 @@
+use crate::cmd::ListArgs;
+use crate::engine::{self, parser};
+use crate::models::{BuildOptions, Post, ProjectPaths};
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ListFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Serialize)]
+struct ListEntry {
+    title: String,
+    slug: String,
+    date: String,
+    tags: Vec<String>,
+    status: &'static str,
+    source: String,
+    output: String,
+}
+
+pub async fn execute(args: ListArgs) -> Result<()> {
+    let paths = ProjectPaths::default();
+    let today = Local::now().date_naive();
+
+    let posts = parser::parse_all_front_matter(&paths.content)?;
+    let entries = list_entries(&posts, &args, &paths, today);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match args.format {
+        ListFormat::Table => write_table(&mut out, &entries)?,
+        ListFormat::Json => write_json(&mut out, &entries)?,
+        ListFormat::Csv => write_csv(&mut out, &entries)?,
+    }
+
+    Ok(())
+}
+
+/// The posts a build with these flags would publish, narrowed by the filters.
+fn list_entries(
+    posts: &[Post],
+    args: &ListArgs,
+    paths: &ProjectPaths,
+    today: NaiveDate,
+) -> Vec<ListEntry> {
+    let options = BuildOptions {
+        drafts: args.drafts,
+        future: args.future,
+        ..Default::default()
+    };
+
+    posts
+        .iter()
+        .filter(|post| options.includes(post) && matches(post, args))
+        .map(|post| ListEntry {
+            title: post.metadata.title.clone(),
+            slug: post.slug.clone(),
+            date: post.metadata.date.format("%Y-%m-%d").to_string(),
+            tags: post.metadata.tags.clone(),
+            status: status(post, today),
+            source: post.source.display().to_string(),
+            output: engine::output_path(&paths.dist, &post.slug)
+                .display()
+                .to_string(),
+        })
+        .collect()
+}
+
+fn matches(post: &Post, filter: &ListArgs) -> bool {
+    let meta = &post.metadata;
+    filter
+        .tag
+        .as_ref()
+        .is_none_or(|tag| meta.tags.contains(tag))
+        && filter.since.is_none_or(|since| meta.date >= since)
+        && filter.until.is_none_or(|until| meta.date <= until)
+}
+
+fn status(post: &Post, today: NaiveDate) -> &'static str {
+    if post.metadata.draft {
+        "draft"
+    } else if post.is_scheduled(today) {
+        "scheduled"
+    } else {
+        "published"
+    }
+}
+
+fn write_table(out: &mut impl Write, entries: &[ListEntry]) -> Result<()> {
+    let headers = ["TITLE", "SLUG", "DATE", "TAGS", "STATUS", "OUTPUT"];
+    let rows: Vec<[String; 6]> = entries
+        .iter()
+        .map(|e| {
+            [
+                e.title.clone(),
+                e.slug.clone(),
+                e.date.clone(),
+                e.tags.join(", "),
+                e.status.to_string(),
+                e.output.clone(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |out: &mut dyn Write, cells: &[&str]| -> io::Result<()> {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())
+    };
+
+    print_row(out, &headers)?;
+    for row in &rows {
+        print_row(out, &row.each_ref().map(String::as_str))?;
+    }
+    writeln!(out, "\n{} post(s)", rows.len())?;
+    Ok(())
+}
+
+fn write_json(out: &mut impl Write, entries: &[ListEntry]) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, entries).context("Failed to write JSON")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn write_csv(out: &mut impl Write, entries: &[ListEntry]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([
+        "title", "slug", "date", "tags", "status", "source", "output",
+    ])?;
+    for e in entries {
+        writer.write_record([
+            e.title.as_str(),
+            &e.slug,
+            &e.date,
+            &e.tags.join(";"),
+            e.status,
+            &e.source,
+            &e.output,
+        ])?;
+    }
+    writer.flush().context("Failed to write CSV")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PostMetadata;
+    use std::path::PathBuf;
+
+    fn day(d: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+    }
+
+    fn post(slug: &str, date: &str, tags: &[&str], draft: bool) -> Post {
+        Post {
+            metadata: PostMetadata {
+                title: slug.to_string(),
+                date: day(date),
+                description: None,
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                draft,
+            },
+            content: String::new(),
+            html: String::new(),
+            slug: format!("posts/{}", slug),
+            source: PathBuf::from(format!("content/posts/{}.md", slug)),
+        }
+    }
+
+    fn args() -> ListArgs {
+        ListArgs {
+            tag: None,
+            since: None,
+            until: None,
+            drafts: false,
+            future: false,
+            format: ListFormat::Table,
+        }
+    }
+
+    fn posts() -> Vec<Post> {
+        vec![
+            post("old", "2024-06-01", &["rust"], false),
+            post("new", "2025-03-01", &["rust", "web"], false),
+            post("wip", "2025-04-01", &["rust"], true),
+            post("later", "2999-01-01", &["web"], false),
+        ]
+    }
+
+    fn slugs(args: &ListArgs) -> Vec<String> {
+        let paths = ProjectPaths::from_root("site");
+        list_entries(&posts(), args, &paths, day("2025-06-01"))
+            .into_iter()
+            .map(|entry| entry.slug)
+            .collect()
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        assert_eq!(slugs(&args()), ["posts/old", "posts/new"]);
+
+        let tagged = ListArgs {
+            tag: Some("web".to_string()),
+            ..args()
+        };
+        assert_eq!(slugs(&tagged), ["posts/new"]);
+
+        let range = ListArgs {
+            since: Some(day("2025-01-01")),
+            until: Some(day("2025-04-01")),
+            drafts: true,
+            ..args()
+        };
+        assert_eq!(slugs(&range), ["posts/new", "posts/wip"]);
+
+        let everything = ListArgs {
+            drafts: true,
+            future: true,
+            ..args()
+        };
+        assert_eq!(slugs(&everything).len(), 4);
+
+        let future_web = ListArgs {
+            tag: Some("web".to_string()),
+            since: Some(day("2025-06-02")),
+            future: true,
+            ..args()
+        };
+        assert_eq!(slugs(&future_web), ["posts/later"]);
+    }
+
+    #[test]
+    fn test_status_is_draft_scheduled_or_published() {
+        let today = day("2025-06-01");
+        assert_eq!(
+            status(&post("a", "2025-01-01", &[], false), today),
+            "published"
+        );
+        assert_eq!(
+            status(&post("a", "2025-06-01", &[], false), today),
+            "published"
+        );
+        assert_eq!(
+            status(&post("a", "2025-06-02", &[], false), today),
+            "scheduled"
+        );
+        // a future draft is still a draft
+        assert_eq!(status(&post("a", "2025-06-02", &[], true), today), "draft");
+    }
+
+    fn entry(title: &str) -> ListEntry {
+        ListEntry {
+            title: title.to_string(),
+            slug: "posts/a".to_string(),
+            date: "2025-01-01".to_string(),
+            tags: vec!["rust".to_string(), "web".to_string()],
+            status: "published",
+            source: "content/posts/a.md".to_string(),
+            output: "dist/posts/a/index.html".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_csv_quotes_commas_and_quotes() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &[entry(r#"Hello, "World""#)]).unwrap();
+
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv,
+            "title,slug,date,tags,status,source,output\n\
+             \"Hello, \"\"World\"\"\",posts/a,2025-01-01,rust;web,published,content/posts/a.md,dist/posts/a/index.html\n"
+        );
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(&record[0], r#"Hello, "World""#);
+    }
+
+    #[test]
+    fn test_table_aligns_columns_and_counts_posts() {
+        let mut out = Vec::new();
+        write_table(&mut out, &[entry("Hi"), entry("A longer title")]).unwrap();
+
+        let table = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[0],
+            "TITLE           SLUG     DATE        TAGS       STATUS     OUTPUT"
+        );
+        assert_eq!(
+            lines[1],
+            "Hi              posts/a  2025-01-01  rust, web  published  dist/posts/a/index.html"
+        );
+        assert_eq!(lines[4], "2 post(s)");
+    }
+
+    #[test]
+    fn test_json_lists_every_field() {
+        let mut out = Vec::new();
+        write_json(&mut out, &[entry("Hi")]).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json[0]["title"], "Hi");
+        assert_eq!(json[0]["tags"], serde_json::json!(["rust", "web"]));
+        assert_eq!(json[0]["status"], "published");
+        assert_eq!(json[0]["output"], "dist/posts/a/index.html");
+    }
+}
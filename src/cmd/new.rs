@@ -0,0 +1,281 @@
+use crate::cmd::init::ensure_no_conflicts;
+use crate::engine::utils;
+use crate::models::ProjectPaths;
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::{fs, path::PathBuf};
+
+const POST_ARCHETYPE: &str = "---
+title: {{ title }}
+date: {{ date }}
+tags: []
+draft: true
+---
+";
+
+const PAGE_ARCHETYPE: &str = "{% extends \"layouts/base.html\" %}
+
+{% block title %} | {{ title }}{% endblock title %}
+
+{% block content %}
+<h1>{{ title }}</h1>
+{% endblock content %}
+";
+
+/// What `new` creates: Markdown content or a page template.
+enum Target {
+    /// `content/<section>/<slug>.md`
+    Content { section: String, slug: String },
+    /// `templates/pages/<slug>.html`
+    Page { slug: String },
+}
+
+/// Create a post or page from an archetype.
+/// - `new post "My Title"` / `new page "About"`
+/// - `new posts/my-title` (title derived from the slug unless given)
+pub async fn execute(target: String, title: Option<String>) -> Result<()> {
+    let paths = ProjectPaths::default();
+
+    let (target, title) = resolve_target(&target, title)?;
+    let (dest, archetype) = match &target {
+        Target::Content { section, slug } => (
+            paths.content.join(section).join(format!("{}.md", slug)),
+            load_archetype(&paths, section, "md")?,
+        ),
+        Target::Page { slug } => (
+            paths.templates.join("pages").join(format!("{}.html", slug)),
+            load_archetype(&paths, "page", "html")?,
+        ),
+    };
+
+    ensure_no_conflicts(
+        "Refusing to overwrite existing file",
+        vec![dest.clone()],
+        "Pick a different title or path.",
+    )?;
+
+    let date = Local::now().date_naive().format("%Y-%m-%d").to_string();
+    let contents = render_archetype(&target, archetype, &title, &date)?;
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    fs::write(&dest, contents)
+        .with_context(|| format!("Failed to write file {}", dest.display()))?;
+
+    println!("📝 Drafted {}", dest.display());
+    if let Target::Content { section, .. } = &target
+        && section.split('/').next() != Some("posts")
+    {
+        println!("⚠️  Only content under content/posts/ is published.");
+    }
+
+    Ok(())
+}
+
+fn resolve_target(target: &str, title: Option<String>) -> Result<(Target, String)> {
+    match target {
+        "post" | "page" => {
+            let title = title
+                .filter(|t| !t.trim().is_empty())
+                .with_context(|| format!("Usage: ferropress new {} \"My Title\"", target))?;
+            let slug = utils::Slugify::from_text(&title);
+            if slug.is_empty() {
+                anyhow::bail!("Title '{}' does not produce a usable slug", title);
+            }
+
+            let target = if target == "post" {
+                Target::Content {
+                    section: "posts".to_string(),
+                    slug,
+                }
+            } else {
+                Target::Page { slug }
+            };
+            Ok((target, title))
+        }
+        path => {
+            let path = path.trim_matches('/');
+            let path = path.strip_suffix(".md").unwrap_or(path);
+            let (section, name) = path
+                .rsplit_once('/')
+                .with_context(|| format!("Expected a path like posts/my-title, got '{}'", path))?;
+
+            let slug = utils::Slugify::from_text(name);
+            let section: Vec<String> = section.split('/').map(utils::Slugify::from_text).collect();
+            if slug.is_empty() || section.iter().any(String::is_empty) {
+                anyhow::bail!("'{}' does not produce a usable path", path);
+            }
+
+            let title = title.unwrap_or_else(|| titleize(&slug));
+            let target = if section == ["pages"] {
+                Target::Page { slug }
+            } else {
+                Target::Content {
+                    section: section.join("/"),
+                    slug,
+                }
+            };
+            Ok((target, title))
+        }
+    }
+}
+
+/// `archetypes/<kind>.<ext>`, then `archetypes/default.<ext>`; `None` means the built-in.
+/// For content, `<kind>` is the top-level section (e.g. `posts` for `posts/2025`).
+fn load_archetype(paths: &ProjectPaths, kind: &str, ext: &str) -> Result<Option<String>> {
+    let kind = kind.split('/').next().unwrap_or(kind);
+    let candidates: [PathBuf; 2] = [
+        paths.archetypes.join(format!("{}.{}", kind, ext)),
+        paths.archetypes.join(format!("default.{}", ext)),
+    ];
+
+    for candidate in candidates {
+        if candidate.is_file() {
+            return fs::read_to_string(&candidate)
+                .map(Some)
+                .with_context(|| format!("Failed to read archetype {}", candidate.display()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fill in an archetype. The built-in post archetype takes the title as a YAML
+/// scalar; a project's own content archetypes get it as typed, since only they
+/// know where it goes. Page archetypes are Tera templates, so the title is kept
+/// from being read as Tera syntax.
+fn render_archetype(
+    target: &Target,
+    archetype: Option<String>,
+    title: &str,
+    date: &str,
+) -> Result<String> {
+    let (archetype, title) = match (archetype, target) {
+        (Some(custom), Target::Content { .. }) => (custom, title.to_string()),
+        (None, Target::Content { .. }) => {
+            let yaml =
+                serde_yaml::to_string(title).context("Failed to encode the title as YAML")?;
+            (POST_ARCHETYPE.to_string(), yaml.trim_end().to_string())
+        }
+        (archetype, Target::Page { .. }) => (
+            archetype.unwrap_or_else(|| PAGE_ARCHETYPE.to_string()),
+            tera_text(title)?,
+        ),
+    };
+    let slug = match target {
+        Target::Content { slug, .. } | Target::Page { slug } => slug,
+    };
+
+    Ok(utils::fill_placeholders(
+        &archetype,
+        &[("title", &title), ("date", date), ("slug", slug)],
+    ))
+}
+
+/// `text` as literal template output: wrapped in a raw block when it contains
+/// Tera delimiters.
+fn tera_text(text: &str) -> Result<String> {
+    if !["{{", "}}", "{%", "%}", "{#", "#}"]
+        .iter()
+        .any(|delimiter| text.contains(delimiter))
+    {
+        return Ok(text.to_string());
+    }
+    if text.contains("endraw") {
+        anyhow::bail!("Title '{}' can't be written into a page template", text);
+    }
+    Ok(format!("{{% raw %}}{}{{% endraw %}}", text))
+}
+
+/// "my-first-post" -> "My First Post"
+fn titleize(slug: &str) -> String {
+    slug.split('-')
+        .filter(|w| !w.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::parser;
+
+    fn post() -> Target {
+        Target::Content {
+            section: "posts".to_string(),
+            slug: "hello".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_archetype_keeps_any_title_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        for title in [r#"Ann "Bo" \o/"#, "yes", "It's: #1", "[draft] {wip}"] {
+            let contents = render_archetype(&post(), None, title, "2025-01-02").unwrap();
+            let path = dir.path().join("hello.md");
+            fs::write(&path, &contents).unwrap();
+
+            let parsed = parser::parse_post(&path, dir.path()).unwrap();
+            assert_eq!(parsed.metadata.title, title, "{contents}");
+            assert!(parsed.metadata.draft);
+        }
+    }
+
+    #[test]
+    fn test_page_archetype_keeps_tera_syntax_in_titles_literal() {
+        let page = Target::Page {
+            slug: "sale".to_string(),
+        };
+        for title in [
+            "{{ price }} off",
+            "50% {% off",
+            "a #} b }}",
+            "Plain & simple",
+        ] {
+            let contents = render_archetype(&page, None, title, "2025-01-02").unwrap();
+            let mut tera = tera::Tera::default();
+            tera.add_raw_templates([
+                (
+                    "layouts/base.html",
+                    "{% block title %}{% endblock title %}|{% block content %}{% endblock content %}",
+                ),
+                ("pages/sale.html", contents.as_str()),
+            ])
+            .unwrap();
+
+            let html = tera
+                .render("pages/sale.html", &tera::Context::new())
+                .unwrap();
+            assert_eq!(
+                html.trim(),
+                format!("| {}|\n<h1>{}</h1>", title, title),
+                "{contents}"
+            );
+        }
+
+        let err = render_archetype(&page, None, "{% endraw %}", "2025-01-02").unwrap_err();
+        assert!(err.to_string().contains("can't be written"), "{err}");
+    }
+
+    #[test]
+    fn test_custom_archetype_gets_the_title_as_typed() {
+        let custom = "---\ntitle: '{{ title }}'\n---\n# {{ title }} ({{ slug }})\n".to_string();
+
+        let contents =
+            render_archetype(&post(), Some(custom), r#"Say "hi""#, "2025-01-02").unwrap();
+
+        assert_eq!(
+            contents,
+            "---\ntitle: 'Say \"hi\"'\n---\n# Say \"hi\" (hello)\n"
+        );
+    }
+}
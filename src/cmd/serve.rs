@@ -1,8 +1,13 @@
+mod caching;
+mod overlay;
+
 use crate::engine;
-use crate::models::{ProjectPaths, ServeMode, SiteConfig};
+use crate::engine::incremental::{Rebuilt, Site};
+use crate::models::{BuildOptions, ProjectPaths, ServeMode};
 use anyhow::{Context, Result};
 use axum::Router;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::process::Command;
 use tokio_util::sync::CancellationToken;
@@ -11,10 +16,16 @@ use tower_livereload::LiveReloadLayer;
 
 pub async fn execute(mode: ServeMode) -> Result<()> {
     let paths = ProjectPaths::default();
-    let config = SiteConfig::load(&paths.config).context("Failed to load site.toml")?;
+    let config = engine::load_config(&paths)?;
     let token = CancellationToken::new();
+    // drafts and scheduled posts are visible while previewing, never in the
+    // production build
+    let options = BuildOptions {
+        drafts: mode == ServeMode::Dev,
+        future: mode == ServeMode::Dev,
+        ..Default::default()
+    };
 
-    paths.clean_dist().context("Failed to clean dist/")?;
     paths
         .create_dist_folders()
         .context("Failed to create dist/")?;
@@ -23,35 +34,102 @@ pub async fn execute(mode: ServeMode) -> Result<()> {
     let livereload = LiveReloadLayer::new();
     let reloader = livereload.reloader();
 
-    let serve_dir = ServeDir::new(&paths.dist)
-        .append_index_html_on_directories(true)
-        .fallback(ServeFile::new(paths.dist.join("404.html")));
-
-    let mut app = Router::new().fallback_service(serve_dir);
+    let build_error = overlay::BuildError::default();
+    let mut app = Router::new().fallback_service(serve_files(&paths.dist, mode));
     if mode == ServeMode::Dev {
-        app = app.layer(livereload);
+        app = app
+            .layer(axum::middleware::from_fn_with_state(
+                build_error.clone(),
+                overlay::inject,
+            ))
+            .layer(livereload);
     }
 
     // In prod, build CSS once up front.
     if mode == ServeMode::Prod {
-        engine::build_css(&paths).context("Tailwind build failed")?;
+        engine::build_css(&paths, &config).context("Tailwind build failed")?;
     }
 
-    // Copy + render once before starting the server
-    engine::copy_static_assets(&paths).context("Copying static assets failed")?;
-    engine::render_site(&paths, &config).context("Rendering site failed")?;
+    // Copy + render once before starting the server. Preview keeps the site in
+    // memory so later changes only re-render what they affect.
+    let mut site = None;
+    if mode == ServeMode::Dev {
+        match Site::build(&paths, &options) {
+            Ok(built) => site = Some(built),
+            // keep previewing so the error shows in the browser until it's fixed
+            Err(e) => {
+                eprintln!("🛑 Build failed: {:#}", e);
+                build_error.set(format!("{:#}", e));
+            }
+        }
+    } else {
+        engine::build_site(&paths, &config, &options)?;
+        app = with_cache_headers(app, &paths);
+    }
 
     if mode == ServeMode::Dev {
-        spawn_tailwind_watch(paths.clone(), token.clone());
+        spawn_tailwind_watch(
+            engine::theme::input_css_file(&paths, &config),
+            paths.output_css_file(),
+            token.clone(),
+        );
 
         let css_out = paths.output_css_file();
         if !wait_for_file(&css_out, 1500).await {
             eprintln!("⚠️  Tailwind output not ready yet: {}", css_out.display());
         }
 
-        spawn_watcher(paths.clone(), reloader.clone(), token.clone());
+        spawn_watcher(
+            paths.clone(),
+            options.clone(),
+            site,
+            reloader.clone(),
+            build_error.clone(),
+            token.clone(),
+        );
+    }
+
+    listen(app, token).await
+}
+
+/// Serve a finished build exactly as it is, e.g. the artifact CI produced.
+pub async fn execute_existing(dir: PathBuf) -> Result<()> {
+    if !dir.is_dir() {
+        anyhow::bail!(
+            "{} is not a directory (run `ferropress build` first)",
+            dir.display()
+        );
+    }
+    println!("📂 Serving {} as-is, without rebuilding", dir.display());
+
+    let app = Router::new().fallback_service(serve_files(&dir, ServeMode::Prod));
+    let paths = ProjectPaths {
+        dist: dir,
+        ..ProjectPaths::default()
+    };
+    let app = with_cache_headers(app, &paths);
+    listen(app, CancellationToken::new()).await
+}
+
+fn serve_files(dir: &Path, mode: ServeMode) -> ServeDir<ServeFile> {
+    let mut serve_dir = ServeDir::new(dir).append_index_html_on_directories(true);
+    let mut not_found = ServeFile::new(dir.join("404.html"));
+    // answer with the .br/.gz variant the client accepts, when the build wrote one
+    if mode == ServeMode::Prod {
+        serve_dir = serve_dir.precompressed_br().precompressed_gzip();
+        not_found = not_found.precompressed_br().precompressed_gzip();
     }
+    serve_dir.fallback(not_found)
+}
 
+fn with_cache_headers(app: Router, paths: &ProjectPaths) -> Router {
+    app.layer(axum::middleware::from_fn_with_state(
+        caching::Fingerprinted::load(paths),
+        caching::headers,
+    ))
+}
+
+async fn listen(app: Router, token: CancellationToken) -> Result<()> {
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     let listener = tokio::net::TcpListener::bind(addr)
         .await
@@ -59,20 +137,17 @@ pub async fn execute(mode: ServeMode) -> Result<()> {
 
     println!("🌍 Listening on {} (http://localhost:3000)", addr);
 
-    let server_token = token.clone();
     axum::serve(listener, app)
         .with_graceful_shutdown(async move {
             let _ = tokio::signal::ctrl_c().await;
             println!("\n🛑 Quenching the flames...");
-            server_token.cancel();
+            token.cancel();
         })
         .await
-        .context("Server exited with error")?;
-
-    Ok(())
+        .context("Server exited with error")
 }
 
-fn spawn_tailwind_watch(paths: ProjectPaths, token: CancellationToken) {
+fn spawn_tailwind_watch(input_css: PathBuf, output_css: PathBuf, token: CancellationToken) {
     tokio::spawn(async move {
         let tw_path = match engine::assets::get_tailwind_exe() {
             Ok(p) => p,
@@ -82,9 +157,6 @@ fn spawn_tailwind_watch(paths: ProjectPaths, token: CancellationToken) {
             }
         };
 
-        let input_css = paths.input_css_file();
-        let output_css = paths.output_css_file();
-
         let mut child = match Command::new(tw_path)
             .arg("-i")
             .arg(&input_css)
@@ -111,7 +183,10 @@ fn spawn_tailwind_watch(paths: ProjectPaths, token: CancellationToken) {
 
 fn spawn_watcher(
     watch_paths: ProjectPaths,
+    options: BuildOptions,
+    mut site: Option<Site>,
     reloader: tower_livereload::Reloader,
+    build_error: overlay::BuildError,
     token: CancellationToken,
 ) {
     tokio::task::spawn_blocking(move || {
@@ -138,6 +213,19 @@ fn spawn_watcher(
             }
         }
 
+        // data/ and themes/ are optional: watch them once they exist, and watch the
+        // project root itself to notice when they're created
+        let root = project_root(&watch_paths);
+        if let Err(e) = watcher.watch(&root, RecursiveMode::NonRecursive) {
+            eprintln!("🛑 Failed to watch {}: {:#}", root.display(), e);
+            return;
+        }
+        let mut optional = [
+            (watch_paths.data.clone(), RecursiveMode::NonRecursive, false),
+            (watch_paths.themes.clone(), RecursiveMode::Recursive, false),
+        ];
+        watch_optional_dirs(&mut watcher, &mut optional);
+
         while !token.is_cancelled() {
             match rx.recv_timeout(std::time::Duration::from_millis(200)) {
                 Ok(Ok(event)) => {
@@ -145,26 +233,52 @@ fn spawn_watcher(
                         continue;
                     }
 
-                    // debounce
+                    // debounce, collecting every path touched in the burst
+                    let mut changed = event.paths;
                     std::thread::sleep(std::time::Duration::from_millis(150));
-                    while rx.try_recv().is_ok() {}
+                    while let Ok(more) = rx.try_recv() {
+                        if let Ok(more) = more {
+                            changed.extend(more.paths);
+                        }
+                    }
+
+                    watch_optional_dirs(&mut watcher, &mut optional);
+                    // the root watch also sees dist/, .git and the like
+                    changed.retain(|path| !is_unwatched_top_level(path, &root, &watch_paths));
+                    if changed.is_empty() {
+                        continue;
+                    }
 
                     println!("♻️  Change detected. Recasting...");
+                    let start = std::time::Instant::now();
 
-                    let result: Result<()> = (|| {
-                        let latest_config = SiteConfig::load(&watch_paths.config)
-                            .context("Failed to load site.toml")?;
-                        engine::copy_static_assets(&watch_paths)
-                            .context("Copying static assets failed")?;
-                        engine::render_site(&watch_paths, &latest_config)
-                            .context("Rendering site failed")?;
-                        Ok(())
-                    })();
+                    // after a failure the in-memory site can't be trusted, so start over
+                    let result = match site.as_mut() {
+                        Some(current) => current.rebuild(&changed),
+                        None => Site::build(&watch_paths, &options).map(|built| {
+                            site = Some(built);
+                            Rebuilt::Everything
+                        }),
+                    };
+                    let result = result.map(|rebuilt| match rebuilt {
+                        Rebuilt::Everything => println!("✅ Full recast in {:?}", start.elapsed()),
+                        Rebuilt::Pages(count) => {
+                            println!("✅ Recast {} page(s) in {:?}", count, start.elapsed())
+                        }
+                    });
+                    if result.is_err() {
+                        site = None;
+                    }
 
+                    // reload either way: browsers pick up the error overlay, or drop it
                     match result {
-                        Ok(()) => reloader.reload(),
-                        Err(e) => eprintln!("🛑 Build failed: {:#}", e),
+                        Ok(()) => build_error.clear(),
+                        Err(e) => {
+                            eprintln!("🛑 Build failed: {:#}", e);
+                            build_error.set(format!("{:#}", e));
+                        }
                     }
+                    reloader.reload();
                 }
                 Ok(Err(e)) => eprintln!("⚠️  Watch event error: {:#}", e),
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
@@ -174,6 +288,42 @@ fn spawn_watcher(
     });
 }
 
+/// The directory holding site.toml.
+fn project_root(paths: &ProjectPaths) -> PathBuf {
+    match paths.config.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Watch each of `dirs` that exists and isn't watched yet. A deleted directory
+/// loses its watch, so it's picked up again if it comes back.
+fn watch_optional_dirs(
+    watcher: &mut impl notify::Watcher,
+    dirs: &mut [(PathBuf, notify::RecursiveMode, bool)],
+) {
+    for (path, mode, watched) in dirs {
+        if !path.is_dir() {
+            *watched = false;
+        } else if !*watched {
+            match watcher.watch(path, *mode) {
+                Ok(()) => *watched = true,
+                Err(e) => eprintln!("⚠️  Failed to watch {}: {:#}", path.display(), e),
+            }
+        }
+    }
+}
+
+/// An entry directly under the project root other than site.toml, data/ or themes/.
+fn is_unwatched_top_level(path: &Path, root: &Path, paths: &ProjectPaths) -> bool {
+    let absolute = |path: &Path| std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = absolute(path);
+    path.parent() == Some(absolute(root).as_path())
+        && ![&paths.config, &paths.data, &paths.themes]
+            .iter()
+            .any(|kept| absolute(kept) == path)
+}
+
 async fn wait_for_file(path: &std::path::Path, timeout_ms: u64) -> bool {
     let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
     while tokio::time::Instant::now() < deadline {
@@ -0,0 +1,209 @@
+use crate::engine::fingerprint::AssetManifest;
+use crate::models::ProjectPaths;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::Response;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// Content-hashed assets never change under the same name.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Everything else can change with the next deploy, so browsers check back
+/// every time; the `ETag` keeps that to a 304 when nothing changed.
+const REVALIDATE: &str = "public, no-cache";
+
+/// URL paths of the fingerprinted assets listed in a build's asset manifest.
+#[derive(Clone, Default)]
+pub struct Fingerprinted(Arc<BTreeSet<String>>);
+
+impl Fingerprinted {
+    pub fn load(paths: &ProjectPaths) -> Self {
+        let static_dir = paths.dist_static();
+        let prefix = static_dir
+            .strip_prefix(&paths.dist)
+            .unwrap_or(&static_dir)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let manifest = AssetManifest::load(&static_dir);
+        let urls = manifest.files().map(|file| format!("/{}/{}", prefix, file));
+        Self(Arc::new(urls.collect()))
+    }
+}
+
+/// Middleware adding `Cache-Control`, `ETag` and `Vary` to files served from a
+/// production build, and answering a matching `If-None-Match` with a 304.
+pub async fn headers(
+    State(fingerprinted): State<Fingerprinted>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let is_read = matches!(*request.method(), Method::GET | Method::HEAD);
+    let if_none_match = request.headers_mut().remove(header::IF_NONE_MATCH);
+    if if_none_match.is_some() {
+        // If-None-Match wins over If-Modified-Since, and only we can evaluate it
+        request.headers_mut().remove(header::IF_MODIFIED_SINCE);
+    }
+    let cache_control = if fingerprinted.0.contains(request.uri().path()) {
+        IMMUTABLE
+    } else {
+        REVALIDATE
+    };
+
+    let mut response = next.run(request).await;
+    let status = response.status();
+    let headers = response.headers_mut();
+    // the same URL may be answered with a .br, .gz or plain file
+    headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    if status != StatusCode::OK && status != StatusCode::NOT_MODIFIED {
+        return response;
+    }
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control),
+    );
+
+    // partial content and ServeDir's own 304s lack the length the tag is built from
+    let Some(etag) = etag(headers) else {
+        return response;
+    };
+    let not_modified = is_read
+        && if_none_match
+            .as_ref()
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| matches_etag(value, &etag));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+
+    if !not_modified {
+        return response;
+    }
+    let (mut parts, _) = response.into_parts();
+    parts.status = StatusCode::NOT_MODIFIED;
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::empty())
+}
+
+/// A tag for the exact bytes being sent, derived from the file's modification
+/// time and size and the encoding picked (each variant is its own file).
+fn etag(headers: &HeaderMap) -> Option<String> {
+    let part = |name| headers.get(name).map(HeaderValue::as_bytes);
+    let modified = part(header::LAST_MODIFIED)?;
+    let length = part(header::CONTENT_LENGTH)?;
+    let encoding = part(header::CONTENT_ENCODING).unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    for value in [modified, length, encoding] {
+        hasher.update(value);
+        hasher.update([0]);
+    }
+    Some(format!("\"{:.16x}\"", hasher.finalize()))
+}
+
+/// `If-None-Match` uses weak comparison, so `W/` prefixes are ignored.
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{serve_files, with_cache_headers};
+    use super::*;
+    use crate::models::ServeMode;
+    use axum::Router;
+    use std::fs;
+    use tempfile::tempdir;
+    use tower::ServiceExt;
+
+    fn production_app(paths: &ProjectPaths) -> Router {
+        let app = Router::new().fallback_service(serve_files(&paths.dist, ServeMode::Prod));
+        with_cache_headers(app, paths)
+    }
+
+    fn site() -> (tempfile::TempDir, ProjectPaths) {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        fs::create_dir_all(paths.dist_js()).unwrap();
+        fs::write(paths.dist_js().join("app.1a2b.js"), "console.log(1)").unwrap();
+        fs::write(
+            paths.dist_static().join("asset-manifest.json"),
+            r#"{"js/app.js":{"file":"js/app.1a2b.js"}}"#,
+        )
+        .unwrap();
+        fs::write(paths.dist.join("index.html"), "<h1>Hello</h1>").unwrap();
+        fs::write(paths.dist.join("index.html.br"), "not really brotli").unwrap();
+        (dir, paths)
+    }
+
+    async fn get(app: Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fingerprinted_assets_are_immutable() {
+        let (_dir, paths) = site();
+
+        let response = get(production_app(&paths), "/static/js/app.1a2b.js", &[]).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], IMMUTABLE);
+        assert_eq!(response.headers()[header::VARY], "accept-encoding");
+    }
+
+    #[tokio::test]
+    async fn test_pages_revalidate_and_answer_a_matching_etag_with_304() {
+        let (_dir, paths) = site();
+
+        let response = get(production_app(&paths), "/", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], REVALIDATE);
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let response = get(
+            production_app(&paths),
+            "/",
+            &[(header::IF_NONE_MATCH, &format!("W/{}", etag))],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        assert_eq!(response.headers()[header::CACHE_CONTROL], REVALIDATE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_each_encoding_gets_its_own_etag() {
+        let (_dir, paths) = site();
+
+        let plain = get(production_app(&paths), "/", &[]).await;
+        let br = get(
+            production_app(&paths),
+            "/",
+            &[(header::ACCEPT_ENCODING, "br")],
+        )
+        .await;
+
+        assert_eq!(br.status(), StatusCode::OK);
+        assert_eq!(br.headers()[header::CONTENT_ENCODING], "br");
+        assert_ne!(br.headers()[header::ETAG], plain.headers()[header::ETAG]);
+    }
+}
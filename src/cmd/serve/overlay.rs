@@ -0,0 +1,196 @@
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Response};
+use std::sync::{Arc, RwLock};
+
+/// The last failed rebuild, shown over every page until a rebuild succeeds.
+#[derive(Clone, Default)]
+pub struct BuildError(Arc<RwLock<Option<String>>>);
+
+impl BuildError {
+    pub fn set(&self, message: String) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(message);
+    }
+
+    pub fn clear(&self) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn get(&self) -> Option<String> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Middleware that injects the error overlay into HTML responses while a build error
+/// is pending. The livereload script triggers the refresh that picks it up or drops it.
+///
+/// tower-livereload's channel only carries "reload", not data, so rather than
+/// pushing the message to the browser the watcher stores it here and reloads;
+/// every page served until the next successful rebuild carries the overlay.
+pub async fn inject(State(error): State<BuildError>, mut request: Request, next: Next) -> Response {
+    let Some(message) = error.get() else {
+        return next.run(request).await;
+    };
+
+    // A 304 would show the browser's cached page without the overlay
+    request.headers_mut().remove(header::IF_NONE_MATCH);
+    request.headers_mut().remove(header::IF_MODIFIED_SINCE);
+
+    let response = next.run(request).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+
+    if !is_html {
+        // a failed first build may not have written the page (or 404.html) at all
+        if response.status() == StatusCode::NOT_FOUND {
+            return (
+                StatusCode::NOT_FOUND,
+                [(header::CACHE_CONTROL, "no-store")],
+                Html(format!(
+                    "<!DOCTYPE html><html lang=\"en\"><head><title>Build failed</title></head><body>{}</body></html>",
+                    overlay_html(&message)
+                )),
+            )
+                .into_response();
+        }
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let mut html = String::from_utf8_lossy(&bytes).into_owned();
+    let overlay = overlay_html(&message);
+    match html.rfind("</body>") {
+        Some(i) => html.insert_str(i, &overlay),
+        None => html.push_str(&overlay),
+    }
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Response::from_parts(parts, Body::from(html))
+}
+
+fn overlay_html(message: &str) -> String {
+    format!(
+        r#"<div id="ferropress-error-overlay" role="alertdialog" aria-label="Build failed" style="position:fixed;inset:0;z-index:2147483647;overflow:auto;padding:2rem;background:rgba(20,12,10,.94);color:#fde7e2;font:14px/1.5 ui-monospace,SFMono-Regular,Menlo,monospace">
+<h2 style="margin:0 0 1rem;color:#ff6b4a;font-size:18px">🛑 FerroPress build failed</h2>
+<pre style="white-space:pre-wrap;margin:0">{}</pre>
+<p style="margin-top:1.5rem;color:#a8a29e">Fix the problem and save. This page reloads once the next build succeeds.</p>
+</div>"#,
+        tera::escape_html(message)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::serve_files;
+    use super::*;
+    use crate::models::ServeMode;
+    use axum::Router;
+    use axum::http::HeaderMap;
+    use axum::routing::get;
+    use std::fs;
+    use tempfile::tempdir;
+    use tower::ServiceExt;
+
+    fn preview_app(dist: &std::path::Path, error: &BuildError) -> Router {
+        Router::new()
+            // shows whether a conditional header made it past the middleware
+            .route(
+                "/headers",
+                get(|headers: HeaderMap| async move {
+                    Html(format!(
+                        "<body>{}</body>",
+                        headers.contains_key(header::IF_NONE_MATCH)
+                    ))
+                }),
+            )
+            .fallback_service(serve_files(dist, ServeMode::Dev))
+            .layer(axum::middleware::from_fn_with_state(error.clone(), inject))
+    }
+
+    async fn get_page(app: Router, uri: &str) -> (Response, String) {
+        let request = Request::get(uri)
+            .header(header::IF_NONE_MATCH, "\"abc\"")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (
+            Response::from_parts(parts, Body::empty()),
+            String::from_utf8(bytes.to_vec()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_overlay_is_injected_while_an_error_is_set() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("index.html"),
+            "<html><body><p>hi</p></body></html>",
+        )
+        .unwrap();
+        fs::write(dir.path().join("app.js"), "let a = 1;").unwrap();
+        let error = BuildError::default();
+        error.set("<b>broken</b> post".to_string());
+
+        let (response, body) = get_page(preview_app(dir.path(), &error), "/").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        assert!(body.starts_with("<html><body><p>hi</p><div id=\"ferropress-error-overlay\""));
+        assert!(body.ends_with("</div></body></html>"), "{body}");
+        assert!(
+            body.contains("&lt;b&gt;broken&lt;&#x2F;b&gt; post"),
+            "{body}"
+        );
+
+        let (_, body) = get_page(preview_app(dir.path(), &error), "/headers").await;
+        assert!(body.starts_with("<body>false"), "{body}");
+
+        let (response, body) = get_page(preview_app(dir.path(), &error), "/app.js").await;
+        assert_eq!(body, "let a = 1;");
+        assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_missing_pages_get_the_fallback_overlay() {
+        let dir = tempdir().unwrap();
+        let error = BuildError::default();
+        error.set("no pages yet".to_string());
+
+        let (response, body) = get_page(preview_app(dir.path(), &error), "/nowhere").await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        assert!(body.starts_with("<!DOCTYPE html>"), "{body}");
+        assert!(body.contains("no pages yet"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_nothing_is_injected_after_clear() {
+        let dir = tempdir().unwrap();
+        let page = "<html><body><p>hi</p></body></html>";
+        fs::write(dir.path().join("index.html"), page).unwrap();
+        let error = BuildError::default();
+        error.set("broken".to_string());
+        error.clear();
+
+        let (response, body) = get_page(preview_app(dir.path(), &error), "/").await;
+        assert_eq!(body, page);
+        assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+
+        let (_, body) = get_page(preview_app(dir.path(), &error), "/headers").await;
+        assert!(body.starts_with("<body>true"), "{body}");
+    }
+}
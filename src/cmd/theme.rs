@@ -0,0 +1,595 @@
+use crate::cmd::ThemeCommand;
+use crate::cmd::init::{ensure_no_conflicts, managed_scaffold_files};
+use crate::engine::theme;
+use crate::models::{ProjectPaths, SiteConfig, ThemeManifest};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    process::Command,
+};
+
+/// Name of the file list written into every packaged theme archive.
+pub const PACKAGE_MANIFEST: &str = "manifest.json";
+
+pub async fn execute(command: ThemeCommand) -> Result<()> {
+    let paths = ProjectPaths::default();
+
+    match command {
+        ThemeCommand::Add {
+            source,
+            name,
+            overwrite,
+        } => add(&paths, &source, name, overwrite),
+        ThemeCommand::List => list(&paths),
+        ThemeCommand::Eject {
+            file,
+            theme,
+            overwrite,
+        } => eject(&paths, &file, theme, overwrite),
+        ThemeCommand::Package { name, out } => package(&paths, name, out),
+    }
+}
+
+/// Install a theme into `themes/<name>`, staging it first so a failed copy
+/// or clone never leaves a half-installed theme behind. Staging happens in
+/// `.ferropress-cache/`, out of sight of `theme list` and the preview watcher
+/// but on the same filesystem, so the final move is a rename.
+fn add(paths: &ProjectPaths, source: &str, name: Option<String>, overwrite: bool) -> Result<()> {
+    for dir in [&paths.themes, &paths.cache] {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    }
+
+    let staging = tempfile::tempdir_in(&paths.cache).context("Failed to create staging dir")?;
+    let staged = staging.path().join("theme");
+
+    let source_path = Path::new(source);
+    if is_archive(source_path) {
+        println!("📦 Unpacking theme archive {}...", source);
+        unpack_archive(source_path, &staged)?;
+    } else if is_git_source(source) {
+        println!("🌱 Cloning theme from {}...", source);
+        clone_repo(source, &staged)?;
+    } else if source_path.is_dir() {
+        println!("📁 Copying theme from {}...", source);
+        copy_theme_dir(source_path, &staged)?;
+    } else {
+        anyhow::bail!(
+            "Theme source {} is not a directory, git repository or .tar.gz archive",
+            source
+        );
+    }
+
+    if !staged.join("templates").is_dir() && !staged.join("static").is_dir() {
+        anyhow::bail!(
+            "{} does not look like a theme (no templates/ or static/)",
+            source
+        );
+    }
+
+    let manifest = ThemeManifest::load(&staged)?;
+    let name = name
+        .or_else(|| Some(manifest.name.clone()).filter(|n| !n.is_empty()))
+        .or_else(|| {
+            source_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().trim_end_matches(".tar").to_string())
+        })
+        .context("Could not determine a theme name; pass --name")?;
+    theme::validate_theme_name(&name)?;
+
+    let dest = paths.theme_dir(&name);
+    if dest.exists() {
+        if !overwrite {
+            anyhow::bail!(
+                "Theme '{}' is already installed at {}. Re-run with --overwrite.",
+                name,
+                dest.display()
+            );
+        }
+        fs::remove_dir_all(&dest)
+            .with_context(|| format!("Failed to remove directory {}", dest.display()))?;
+    }
+
+    fs::rename(&staged, &dest)
+        .with_context(|| format!("Failed to install theme into {}", dest.display()))?;
+
+    println!("✅ Theme '{}' installed in {}", name, dest.display());
+    println!("Next: set theme = \"{}\" in site.toml", name);
+    Ok(())
+}
+
+fn list(paths: &ProjectPaths) -> Result<()> {
+    let active = active_theme(paths);
+
+    let mut themes = Vec::new();
+    if paths.themes.is_dir() {
+        for entry in fs::read_dir(&paths.themes)
+            .with_context(|| format!("Failed to read {}", paths.themes.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                themes.push(path);
+            }
+        }
+    }
+    themes.sort();
+
+    if themes.is_empty() {
+        println!("No themes installed. Add one with: ferropress theme add <path-or-git-url>");
+        return Ok(());
+    }
+
+    for dir in themes {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        let manifest = ThemeManifest::load(&dir)?;
+        let marker = if active.as_deref() == Some(name.as_ref()) {
+            "*"
+        } else {
+            " "
+        };
+
+        print!(
+            "{} {} {}",
+            marker,
+            name,
+            manifest.version.as_deref().unwrap_or("-")
+        );
+        match manifest.description {
+            Some(description) => println!("  {}", description),
+            None => println!(),
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a file, or every file under a directory, from a theme's `templates/`
+/// or `static/` into the project at the same path.
+fn eject(
+    paths: &ProjectPaths,
+    file: &Path,
+    theme_name: Option<String>,
+    overwrite: bool,
+) -> Result<()> {
+    let theme_name = theme_name
+        .or_else(|| active_theme(paths))
+        .context("No theme given and no `theme` set in site.toml; pass --theme")?;
+    theme::validate_theme_name(&theme_name)?;
+    let theme_dir = paths.theme_dir(&theme_name);
+
+    let relative = normalize_relative(file)?;
+    let (layer, project_dir) = if relative.starts_with("templates") {
+        ("templates", &paths.templates)
+    } else if relative.starts_with("static") {
+        ("static", &paths.static_files)
+    } else {
+        anyhow::bail!(
+            "Can only eject files under templates/ or static/, got {}",
+            file.display()
+        );
+    };
+    let inner = relative.strip_prefix(layer).unwrap_or(&relative);
+
+    let src = theme_dir.join(&relative);
+    let inner_files: Vec<PathBuf> = if src.is_file() {
+        vec![inner.to_path_buf()]
+    } else if src.is_dir() {
+        theme::layered_files(std::slice::from_ref(&src))?
+            .into_keys()
+            .map(|file| inner.join(file))
+            .collect()
+    } else {
+        anyhow::bail!(
+            "Theme '{}' has no file or directory {}",
+            theme_name,
+            relative.display()
+        );
+    };
+
+    let dests = managed_scaffold_files(project_dir, &inner_files);
+    if !overwrite {
+        ensure_no_conflicts(
+            "Project file already exists",
+            dests.clone(),
+            "Re-run with --overwrite to replace them with the theme's copies.",
+        )?;
+    }
+
+    let theme_layer = theme_dir.join(layer);
+    for (inner_file, dest) in inner_files.iter().zip(&dests) {
+        let src = theme_layer.join(inner_file);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        fs::copy(&src, dest)
+            .with_context(|| format!("Failed to copy {} to {}", src.display(), dest.display()))?;
+        println!("✅ Ejected {} -> {}", src.display(), dest.display());
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct PackageManifest {
+    name: String,
+    version: String,
+    ferropress: String,
+    files: Vec<PackageFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PackageFile {
+    path: String,
+    size: u64,
+    sha256: String,
+}
+
+/// Write `<name>-<version>.tar.gz` holding the theme plus a `manifest.json` file list.
+fn package(paths: &ProjectPaths, name: Option<String>, out: Option<PathBuf>) -> Result<()> {
+    let name = name
+        .or_else(|| active_theme(paths))
+        .context("No theme given and no `theme` set in site.toml")?;
+    theme::validate_theme_name(&name)?;
+    let theme_dir = paths.theme_dir(&name);
+    if !theme_dir.is_dir() {
+        anyhow::bail!("Theme '{}' not found at {}", name, theme_dir.display());
+    }
+
+    let manifest = ThemeManifest::load(&theme_dir)?;
+    let version = manifest.version.unwrap_or_else(|| "0.0.0".to_string());
+
+    let files = theme::layered_files(std::slice::from_ref(&theme_dir))?;
+    let mut listed = Vec::new();
+    for (relative, path) in &files {
+        if relative == PACKAGE_MANIFEST || relative.starts_with(".git/") {
+            continue;
+        }
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        listed.push(PackageFile {
+            path: relative.clone(),
+            size: bytes.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+        });
+    }
+
+    let package_manifest = PackageManifest {
+        name: name.clone(),
+        version: version.clone(),
+        ferropress: env!("CARGO_PKG_VERSION").to_string(),
+        files: listed,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&package_manifest)?;
+
+    let out = out.unwrap_or_else(|| PathBuf::from(format!("{}-{}.tar.gz", name, version)));
+    let archive = fs::File::create(&out)
+        .with_context(|| format!("Failed to create archive {}", out.display()))?;
+    let encoder = flate2::write::GzEncoder::new(archive, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    for file in &package_manifest.files {
+        builder
+            .append_path_with_name(&files[&file.path], &file.path)
+            .with_context(|| format!("Failed to add {} to archive", file.path))?;
+    }
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, PACKAGE_MANIFEST, manifest_json.as_slice())
+        .context("Failed to add manifest to archive")?;
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .with_context(|| format!("Failed to finish archive {}", out.display()))?;
+
+    println!(
+        "📦 Packaged theme '{}' {} ({} files) -> {}",
+        name,
+        version,
+        package_manifest.files.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+fn active_theme(paths: &ProjectPaths) -> Option<String> {
+    SiteConfig::load_table(&paths.config)
+        .ok()?
+        .get("theme")?
+        .as_str()
+        .map(String::from)
+}
+
+/// Reject absolute paths and `..` so ejection can't escape the theme or project.
+fn normalize_relative(path: &Path) -> Result<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            _ => anyhow::bail!(
+                "Expected a relative path inside the theme, got {}",
+                path.display()
+            ),
+        }
+    }
+    Ok(out)
+}
+
+fn is_archive(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    path.is_file() && (name.ends_with(".tar.gz") || name.ends_with(".tgz"))
+}
+
+fn is_git_source(source: &str) -> bool {
+    source.contains("://")
+        || source.starts_with("git@")
+        || source.ends_with(".git")
+        || Path::new(source).join(".git").exists()
+}
+
+fn clone_repo(source: &str, dest: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--depth", "1", source])
+        .arg(dest)
+        .output()
+        .context("Failed to execute git clone (is git installed?)")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    // Vendor the theme into the project rather than nesting a repository
+    let git_dir = dest.join(".git");
+    if git_dir.exists() {
+        fs::remove_dir_all(&git_dir)
+            .with_context(|| format!("Failed to remove {}", git_dir.display()))?;
+    }
+    Ok(())
+}
+
+fn copy_theme_dir(src: &Path, dest: &Path) -> Result<()> {
+    for (relative, path) in theme::layered_files(&[src.to_path_buf()])? {
+        if relative.starts_with(".git/") {
+            continue;
+        }
+        let target = dest.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        fs::copy(&path, &target).with_context(|| {
+            format!("Failed to copy {} to {}", path.display(), target.display())
+        })?;
+    }
+    Ok(())
+}
+
+fn unpack_archive(archive: &Path, dest: &Path) -> Result<()> {
+    let file = fs::File::open(archive)
+        .with_context(|| format!("Failed to open archive {}", archive.display()))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+
+    fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create directory {}", dest.display()))?;
+    tar.unpack(dest)
+        .with_context(|| format!("Failed to unpack archive {}", archive.display()))?;
+
+    verify_package(dest).with_context(|| format!("Refusing to install {}", archive.display()))
+}
+
+/// Check the unpacked files against the archive's manifest: every listed file
+/// must match its size and checksum, and nothing else may be present.
+fn verify_package(dir: &Path) -> Result<()> {
+    let manifest_path = dir.join(PACKAGE_MANIFEST);
+    let bytes = fs::read(&manifest_path).with_context(|| {
+        format!(
+            "The archive has no {} (make it with `ferropress theme package`)",
+            PACKAGE_MANIFEST
+        )
+    })?;
+    let manifest: PackageManifest =
+        serde_json::from_slice(&bytes).with_context(|| format!("Invalid {}", PACKAGE_MANIFEST))?;
+    // The file list only matters for distribution; don't keep it in the installed theme
+    fs::remove_file(&manifest_path)
+        .with_context(|| format!("Failed to remove {}", manifest_path.display()))?;
+
+    let mut unpacked = theme::layered_files(&[dir.to_path_buf()])?;
+    for file in &manifest.files {
+        let path = unpacked
+            .remove(&file.path)
+            .with_context(|| format!("{} is listed in the manifest but missing", file.path))?;
+        let bytes =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        if bytes.len() as u64 != file.size || format!("{:x}", Sha256::digest(&bytes)) != file.sha256
+        {
+            anyhow::bail!("{} does not match its checksum in the manifest", file.path);
+        }
+    }
+    if let Some(extra) = unpacked.keys().next() {
+        anyhow::bail!("{} is not listed in the manifest", extra);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// A project with `themes/forge` holding a template and a stylesheet.
+    fn project_with_theme(root: &Path) -> ProjectPaths {
+        let paths = ProjectPaths::from_root(root);
+        let theme = paths.theme_dir("forge");
+        fs::create_dir_all(theme.join("templates/partials")).unwrap();
+        fs::create_dir_all(theme.join("static/css")).unwrap();
+        fs::write(
+            theme.join("theme.toml"),
+            "name = \"forge\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        fs::write(theme.join("templates/partials/nav.html"), "<nav></nav>").unwrap();
+        fs::write(
+            theme.join("templates/partials/footer.html"),
+            "<footer></footer>",
+        )
+        .unwrap();
+        fs::write(
+            theme.join("static/css/input.css"),
+            "@import \"tailwindcss\";",
+        )
+        .unwrap();
+        paths
+    }
+
+    fn write_archive(path: &Path, files: &[(&str, &[u8])]) {
+        let encoder = flate2::write::GzEncoder::new(
+            fs::File::create(path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *contents).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn manifest_json(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let files = files
+            .iter()
+            .map(|(path, contents)| PackageFile {
+                path: path.to_string(),
+                size: contents.len() as u64,
+                sha256: format!("{:x}", Sha256::digest(contents)),
+            })
+            .collect();
+        serde_json::to_vec(&PackageManifest {
+            name: "forge".to_string(),
+            version: "1.2.0".to_string(),
+            ferropress: env!("CARGO_PKG_VERSION").to_string(),
+            files,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_package_then_add_round_trips() {
+        let dir = tempdir().unwrap();
+        let source = project_with_theme(&dir.path().join("source"));
+        let archive = dir.path().join("forge.tar.gz");
+        package(&source, Some("forge".to_string()), Some(archive.clone())).unwrap();
+
+        let target = ProjectPaths::from_root(dir.path().join("target"));
+        add(&target, archive.to_str().unwrap(), None, false).unwrap();
+
+        let installed = theme::layered_files(&[target.theme_dir("forge")]).unwrap();
+        let original = theme::layered_files(&[source.theme_dir("forge")]).unwrap();
+        assert_eq!(
+            installed.keys().collect::<Vec<_>>(),
+            original.keys().collect::<Vec<_>>()
+        );
+        // nothing left behind: no manifest in the theme, no staging directories
+        assert!(!target.theme_dir("forge").join(PACKAGE_MANIFEST).exists());
+        assert_eq!(fs::read_dir(&target.themes).unwrap().count(), 1);
+        assert_eq!(fs::read_dir(&target.cache).unwrap().count(), 0);
+
+        let err = add(&target, archive.to_str().unwrap(), None, false).unwrap_err();
+        assert!(err.to_string().contains("already installed"), "{err}");
+    }
+
+    #[test]
+    fn test_add_rejects_archives_that_dont_match_their_manifest() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path().join("project"));
+        let nav: (&str, &[u8]) = ("templates/nav.html", b"<nav></nav>");
+        let archive = dir.path().join("forge.tar.gz");
+
+        let tampered = manifest_json(&[("templates/nav.html", b"<nav>original</nav>")]);
+        write_archive(&archive, &[nav, (PACKAGE_MANIFEST, &tampered)]);
+        let err = format!(
+            "{:#}",
+            add(&paths, archive.to_str().unwrap(), None, false).unwrap_err()
+        );
+        assert!(err.contains("templates/nav.html does not match"), "{err}");
+
+        let listed = manifest_json(&[nav]);
+        let extra: (&str, &[u8]) = ("templates/extra.html", b"<script></script>");
+        write_archive(&archive, &[nav, extra, (PACKAGE_MANIFEST, &listed)]);
+        let err = format!(
+            "{:#}",
+            add(&paths, archive.to_str().unwrap(), None, false).unwrap_err()
+        );
+        assert!(err.contains("templates/extra.html is not listed"), "{err}");
+
+        write_archive(&archive, &[nav]);
+        let err = format!(
+            "{:#}",
+            add(&paths, archive.to_str().unwrap(), None, false).unwrap_err()
+        );
+        assert!(err.contains("has no manifest.json"), "{err}");
+
+        assert!(!paths.theme_dir("forge").exists());
+    }
+
+    #[test]
+    fn test_eject_directory_refuses_to_overwrite() {
+        let dir = tempdir().unwrap();
+        let paths = project_with_theme(dir.path());
+        let forge = Some("forge".to_string());
+
+        eject(
+            &paths,
+            Path::new("templates/partials"),
+            forge.clone(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(paths.templates.join("partials/nav.html")).unwrap(),
+            "<nav></nav>"
+        );
+        assert!(paths.templates.join("partials/footer.html").is_file());
+
+        fs::write(paths.templates.join("partials/nav.html"), "mine").unwrap();
+        let err = eject(
+            &paths,
+            Path::new("templates/partials"),
+            forge.clone(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("partials/nav.html"), "{err}");
+        assert!(err.to_string().contains("partials/footer.html"), "{err}");
+        assert_eq!(
+            fs::read_to_string(paths.templates.join("partials/nav.html")).unwrap(),
+            "mine"
+        );
+
+        eject(
+            &paths,
+            Path::new("templates/partials/nav.html"),
+            forge.clone(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(paths.templates.join("partials/nav.html")).unwrap(),
+            "<nav></nav>"
+        );
+        assert!(eject(&paths, Path::new("../site.toml"), forge, false).is_err());
+    }
+}
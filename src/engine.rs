@@ -1,48 +1,152 @@
+pub mod a11y;
 pub mod assets;
+pub mod cache;
+pub mod check;
+pub mod collections;
+pub mod compress;
+pub mod csp;
+pub mod data;
+pub mod diagnostics;
+pub mod filters;
+pub mod fingerprint;
+pub mod functions;
+pub mod html;
+pub mod incremental;
+pub mod links;
+pub mod manifest;
+pub mod minify;
 pub mod parser;
 pub mod render;
+pub mod theme;
 pub mod utils;
 
 use crate::{
-    engine::render::Renderer,
-    models::{ProjectPaths, RenderedPage, SiteConfig},
+    engine::{fingerprint::AssetManifest, render::Renderer},
+    models::{BuildOptions, ProjectPaths, RenderedPage, SiteConfig},
 };
 use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
-pub fn render_site(paths: &ProjectPaths, config: &SiteConfig) -> Result<()> {
+/// Load site.toml along with the global data files it exposes to templates.
+pub fn load_config(paths: &ProjectPaths) -> Result<SiteConfig> {
+    let mut config = theme::load_site_config(paths).context("Failed to load site.toml")?;
+    config.data = data::load_data_dir(&paths.data).context("Failed to load data files")?;
+    config.collection_entries =
+        collections::resolve_collections(&config).context("Failed to resolve collections")?;
+    Ok(config)
+}
+
+/// The production pipeline once the stylesheet is built: copy and fingerprint
+/// static files, render every page, optionally precompress the results, then
+/// prune what earlier builds left behind.
+pub fn build_site(
+    paths: &ProjectPaths,
+    config: &SiteConfig,
+    options: &BuildOptions,
+) -> Result<Vec<RenderedPage>> {
+    copy_static_assets(paths, config).context("Copying static assets failed")?;
+    let assets =
+        AssetManifest::write(paths, config).context("Fingerprinting static assets failed")?;
+    let pages = render_site(paths, config, options, &assets).context("Rendering site failed")?;
+
+    let mut outputs =
+        manifest::output_files(paths, config, pages.iter().map(|page| page.slug.as_str()))?;
+    outputs.extend(assets.outputs(paths));
+    outputs.extend(csp::outputs(&config.build.csp));
+    if options.compress {
+        println!("🗜️  Precompressing text outputs...");
+        let compressed =
+            compress::compress_outputs(paths, &outputs).context("Compressing outputs failed")?;
+        outputs.extend(compressed);
+    }
+
+    prune_outputs(paths, &outputs).context("Pruning stale outputs failed")?;
+    Ok(pages)
+}
+
+/// Render every page into dist/ and return what was written.
+fn render_site(
+    paths: &ProjectPaths,
+    config: &SiteConfig,
+    options: &BuildOptions,
+    assets: &AssetManifest,
+) -> Result<Vec<RenderedPage>> {
     // initialize renderer
-    let renderer = Renderer::new(&paths.templates)?;
+    let mut renderer = Renderer::new(&theme::template_dirs(paths, config), assets.clone())?;
 
-    // process content
-    let posts = parser::parse_all_posts(&paths.content)?;
-    let rendered_posts = renderer.render_all_posts(&posts, config)?;
+    // process content, reusing unchanged posts from the last build
+    let cache = cache::PostCache::load(&paths.cache);
+    let mut posts = parser::parse_all_posts_cached(&paths.content, Some(&cache))?;
+    cache.save()?;
+    posts.retain(|post| options.includes(post));
+    renderer.register_site_functions(&posts, config);
 
-    let rendered_pages = renderer.render_all_pages(&posts, paths, config)?;
+    let mut rendered = renderer
+        .render_all(&posts, config)
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    if config.build.minify_html {
+        rendered
+            .par_iter_mut()
+            .for_each(|page| page.html = minify::minify_html(&page.html));
+    }
+    // hashes cover inline code exactly as written, so this runs after minifying
+    if let Some(headers) = csp::apply(&mut rendered, &config.build.csp) {
+        manifest::write_if_changed(&paths.dist.join(csp::HEADERS_FILE), headers.as_bytes())?;
+    }
 
     // write HTML files
-    write_pages(&paths.dist, rendered_posts)?;
-    write_pages(&paths.dist, rendered_pages)?;
+    write_pages(&paths.dist, &rendered)?;
 
+    Ok(rendered)
+}
+
+/// Remove files earlier builds wrote that this one no longer produces,
+/// e.g. pages of deleted or renamed posts. `extra` lists outputs beyond pages,
+/// static files and the stylesheet (relative to dist/).
+pub fn prune_dist<'a>(
+    paths: &ProjectPaths,
+    config: &SiteConfig,
+    slugs: impl IntoIterator<Item = &'a str>,
+    extra: impl IntoIterator<Item = String>,
+) -> Result<()> {
+    let mut outputs = manifest::output_files(paths, config, slugs)?;
+    outputs.extend(extra);
+    prune_outputs(paths, &outputs)
+}
+
+fn prune_outputs(paths: &ProjectPaths, outputs: &BTreeSet<String>) -> Result<()> {
+    let removed = manifest::prune(paths, outputs)?;
+    if removed > 0 {
+        println!("🧹 Pruned {} stale file(s) from dist/.", removed);
+    }
     Ok(())
 }
 
-pub fn build_css(paths: &ProjectPaths) -> Result<()> {
+pub fn build_css(paths: &ProjectPaths, config: &SiteConfig) -> Result<()> {
     paths.create_dist_folders()?;
 
     println!("🎨 TAILWIND: Weaving v4 styles...");
     let tailwind_path = crate::engine::assets::get_tailwind_exe()?;
 
-    let input_css = paths.input_css_file();
+    let input_css = theme::input_css_file(paths, config);
     let output_css = paths.output_css_file();
+    // Tailwind always rewrites its output; go through a temp file so an
+    // unchanged stylesheet keeps its mtime
+    let temp_css = tempfile::Builder::new()
+        .suffix(".css")
+        .tempfile()
+        .context("Failed to create a temporary stylesheet")?;
 
     let status = std::process::Command::new(tailwind_path)
         .arg("-i")
         .arg(&input_css)
         .arg("-o")
-        .arg(&output_css)
+        .arg(temp_css.path())
         .arg("--minify")
         .stdout(Stdio::null())
         .stderr(Stdio::inherit())
@@ -53,75 +157,131 @@ pub fn build_css(paths: &ProjectPaths) -> Result<()> {
         anyhow::bail!("TailwindCSS exited with status: {}", status);
     }
 
+    let css = fs::read(temp_css.path()).context("Failed to read Tailwind output")?;
+    manifest::write_if_changed(&output_css, &css)?;
     Ok(())
 }
 
-pub fn copy_static_assets(paths: &ProjectPaths) -> Result<()> {
+/// Copy theme and project static files into dist/static (project files win),
+/// leaving files that are already up to date untouched.
+/// `css/` is skipped because Tailwind writes the stylesheet itself.
+pub fn copy_static_assets(paths: &ProjectPaths, config: &SiteConfig) -> Result<()> {
     paths.create_dist_folders()?;
 
-    if paths.static_files.exists() {
-        for entry in fs::read_dir(&paths.static_files)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() && path.file_name().unwrap_or_default() == "css" {
-                continue;
-            }
-
-            let dst = paths.dist_static().join(entry.file_name());
-            if path.is_dir() {
-                copy_dir_all(&path, &dst)?;
-            } else {
-                fs::copy(&path, &dst)?;
-            }
+    let files = theme::layered_files(&theme::static_dirs(paths, config))?;
+    for (relative, src) in files {
+        if relative.starts_with("css/") {
+            continue;
         }
+
+        manifest::copy_if_changed(&src, &paths.dist_static().join(&relative))?;
     }
     Ok(())
 }
 
-fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result<()> {
-    let src = src.as_ref();
-    let dst = dst.as_ref();
+/// Where a page with `slug` is written under `dist_root`.
+pub fn output_path(dist_root: &Path, slug: &str) -> PathBuf {
+    let slug = slug.trim_matches('/');
 
-    fs::create_dir_all(dst)?;
+    // Pretty URL rules:
+    // - "index" -> /index.html
+    // - "404"   -> /404.html   (so ServeFile fallback works)
+    // - otherwise -> /<slug>/index.html
+    if slug == "index" {
+        dist_root.join("index.html")
+    } else if slug == "404" {
+        dist_root.join("404.html")
+    } else {
+        dist_root.join(slug).join("index.html")
+    }
+}
 
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let dest_path = dst.join(entry.file_name());
+/// [`output_path`] relative to dist/, `/`-separated.
+pub fn output_file(slug: &str) -> String {
+    output_path(Path::new(""), slug)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
 
-        if ty.is_dir() {
-            copy_dir_all(entry.path(), &dest_path)?;
+/// One error per output file that more than one page would write, e.g. a
+/// collection entry whose slug matches a post.
+pub fn duplicate_outputs(pages: &[RenderedPage]) -> Vec<anyhow::Error> {
+    let mut writers: BTreeMap<String, &Path> = BTreeMap::new();
+    let mut errors = Vec::new();
+    for page in pages {
+        let file = output_file(&page.slug);
+        if let Some(other) = writers.get(&file) {
+            errors.push(anyhow::anyhow!(
+                "{:?} and {:?} both write dist/{}",
+                other,
+                page.source,
+                file
+            ));
         } else {
-            fs::copy(entry.path(), dest_path)?;
+            writers.insert(file, &page.source);
         }
     }
-    Ok(())
+    errors
 }
 
-fn write_pages(dist_root: &Path, pages: Vec<RenderedPage>) -> Result<()> {
-    for page in pages {
-        let slug = page.slug.trim_matches('/');
-
-        // Pretty URL rules:
-        // - "index" -> /index.html
-        // - "404"   -> /404.html   (so ServeFile fallback works)
-        // - otherwise -> /<slug>/index.html
-        let output_path = if slug == "index" {
-            dist_root.join("index.html")
-        } else if slug == "404" {
-            dist_root.join("404.html")
-        } else {
-            dist_root.join(slug).join("index.html")
-        };
+/// Write each page to its output file. Pages sharing an output would race in the
+/// parallel write, so those are rejected before anything is written.
+fn write_pages(dist_root: &Path, pages: &[RenderedPage]) -> Result<()> {
+    if let Some(duplicate) = duplicate_outputs(pages).into_iter().next() {
+        return Err(duplicate.context("Two pages have the same output file"));
+    }
 
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
-        }
+    pages.par_iter().try_for_each(|page| {
+        let output_path = output_path(dist_root, &page.slug);
+        manifest::write_if_changed(&output_path, page.html.as_bytes())
+            .with_context(|| format!("Failed to write page: {:?}", output_path))
+            .map(|_| ())
+    })
+}
 
-        fs::write(&output_path, page.html)
-            .with_context(|| format!("Failed to write page: {:?}", output_path))?;
+/// Size the thread pool used for parsing, rendering and writing.
+/// `None` uses one thread per core.
+pub fn configure_jobs(jobs: Option<usize>) -> Result<()> {
+    let Some(jobs) = jobs else {
+        return Ok(());
+    };
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build_global()
+        .context("Failed to configure the build thread pool")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn page(slug: &str, source: &str) -> RenderedPage {
+        RenderedPage {
+            slug: slug.to_string(),
+            html: format!("<p>{}</p>", source),
+            source: PathBuf::from(source),
+        }
     }
 
-    Ok(())
+    #[test]
+    fn test_write_pages_rejects_duplicate_outputs_before_writing() {
+        let dist = tempdir().unwrap();
+        let pages = [
+            page("about", "templates/pages/about.html"),
+            page("posts/hello", "content/posts/hello.md"),
+            page("posts/hello/", "templates/item.html"),
+        ];
+
+        let err = format!("{:#}", write_pages(dist.path(), &pages).unwrap_err());
+
+        assert!(
+            err.contains("\"content/posts/hello.md\" and \"templates/item.html\" both write dist/posts/hello/index.html"),
+            "{err}"
+        );
+        assert!(!dist.path().join("about/index.html").exists());
+
+        write_pages(dist.path(), &pages[..2]).unwrap();
+        assert!(dist.path().join("posts/hello/index.html").is_file());
+    }
 }
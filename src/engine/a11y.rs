@@ -0,0 +1,257 @@
+use crate::engine::html::{self, Tag, Token};
+use crate::engine::output_file;
+use crate::models::{A11yConfig, RenderedPage, Rule, Severity};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+
+/// One accessibility problem on a rendered page.
+#[derive(Debug)]
+pub struct Finding {
+    pub rule: Rule,
+    pub severity: Severity,
+    /// Output file, relative to dist/
+    pub page: String,
+    /// Markdown post or template the page was rendered from
+    pub source: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} ({}): {}",
+            self.rule,
+            self.page,
+            self.source.display(),
+            self.message
+        )
+    }
+}
+
+/// Lint every page, skipping rules set to `off` in `[a11y]`.
+pub fn lint_pages(pages: &[RenderedPage], config: &A11yConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for page in pages {
+        let file = output_file(&page.slug);
+
+        for (rule, message) in lint_html(&page.html) {
+            let severity = config.severity(rule);
+            if severity == Severity::Off {
+                continue;
+            }
+
+            findings.push(Finding {
+                rule,
+                severity,
+                page: file.clone(),
+                source: page.source.clone(),
+                message,
+            });
+        }
+    }
+
+    findings
+}
+
+/// Open `<a>` being checked for accessible text.
+struct OpenLink {
+    href: String,
+    labelled: bool,
+}
+
+fn lint_html(page: &str) -> Vec<(Rule, String)> {
+    let tokens = html::tokenize(page);
+    let mut found = Vec::new();
+
+    // labels may come after their field, so collect them up front
+    let label_targets: HashSet<&str> = html::start_tags(&tokens)
+        .filter(|tag| tag.name == "label")
+        .filter_map(|tag| tag.attr("for"))
+        .collect();
+
+    let mut ids = HashSet::new();
+    let mut last_heading: Option<u8> = None;
+    let mut link: Option<OpenLink> = None;
+    let mut label_depth = 0usize;
+
+    for token in &tokens {
+        match token {
+            Token::Start(tag) => {
+                if let Some(id) = tag.attr("id")
+                    && !ids.insert(id)
+                {
+                    found.push((
+                        Rule::DuplicateId,
+                        format!("id \"{}\" is used more than once", id),
+                    ));
+                }
+
+                match tag.name.as_str() {
+                    "html" if tag.attr("lang").is_none_or(|l| l.trim().is_empty()) => {
+                        found.push((Rule::HtmlLang, "<html> has no lang attribute".to_string()));
+                    }
+                    "img" => {
+                        if !tag.has_attr("alt") {
+                            found.push((
+                                Rule::ImgAlt,
+                                format!(
+                                    "<img src=\"{}\"> has no alt text",
+                                    tag.attr("src").unwrap_or_default()
+                                ),
+                            ));
+                        }
+                        if let Some(open) = &mut link
+                            && tag.attr("alt").is_some_and(|alt| !alt.trim().is_empty())
+                        {
+                            open.labelled = true;
+                        }
+                    }
+                    "a" if tag.has_attr("href") => {
+                        link = Some(OpenLink {
+                            href: tag.attr("href").unwrap_or_default().to_string(),
+                            labelled: has_aria_label(tag),
+                        });
+                    }
+                    "label" => label_depth += 1,
+                    "input" | "select" | "textarea"
+                        if needs_label(tag)
+                            && label_depth == 0
+                            && !has_aria_label(tag)
+                            && !tag.attr("id").is_some_and(|id| label_targets.contains(id)) =>
+                    {
+                        let name = tag.attr("name").or(tag.attr("id")).unwrap_or_default();
+                        found.push((
+                            Rule::FormLabel,
+                            format!("<{} name=\"{}\"> has no label", tag.name, name),
+                        ));
+                    }
+                    _ => {}
+                }
+
+                if let Some(level) = heading_level(&tag.name) {
+                    if let Some(last) = last_heading
+                        && level > last + 1
+                    {
+                        found.push((
+                            Rule::HeadingOrder,
+                            format!("<h{}> follows <h{}>, skipping a level", level, last),
+                        ));
+                    }
+                    last_heading = Some(level);
+                }
+            }
+            Token::Text(text) => {
+                if let Some(open) = &mut link
+                    && !html::decode_entities(text).trim().is_empty()
+                {
+                    open.labelled = true;
+                }
+            }
+            Token::End { name, .. } => match name.as_str() {
+                "a" => {
+                    if let Some(open) = link.take()
+                        && !open.labelled
+                    {
+                        found.push((
+                            Rule::LinkText,
+                            format!("link to \"{}\" has no text", open.href),
+                        ));
+                    }
+                }
+                "label" => label_depth = label_depth.saturating_sub(1),
+                _ => {}
+            },
+            Token::RawText(_) | Token::Other(_) => {}
+        }
+    }
+
+    found
+}
+
+fn has_aria_label(tag: &Tag) -> bool {
+    ["aria-label", "aria-labelledby", "title"]
+        .iter()
+        .any(|attr| tag.attr(attr).is_some_and(|v| !v.trim().is_empty()))
+}
+
+/// Buttons and hidden inputs carry their own text or aren't shown at all.
+fn needs_label(tag: &Tag) -> bool {
+    tag.name != "input"
+        || !matches!(
+            tag.attr("type")
+                .unwrap_or("text")
+                .to_ascii_lowercase()
+                .as_str(),
+            "hidden" | "submit" | "button" | "reset" | "image"
+        )
+}
+
+fn heading_level(name: &str) -> Option<u8> {
+    match name.as_bytes() {
+        [b'h', level @ b'1'..=b'6'] => Some(level - b'0'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(html: &str) -> Vec<Rule> {
+        lint_html(html).into_iter().map(|(rule, _)| rule).collect()
+    }
+
+    #[test]
+    fn test_each_rule_fires_on_its_own_problem() {
+        assert_eq!(rules("<html><body></body></html>"), [Rule::HtmlLang]);
+        assert_eq!(rules(r#"<img src="a.png">"#), [Rule::ImgAlt]);
+        assert_eq!(rules("<h1>A</h1><h3>B</h3>"), [Rule::HeadingOrder]);
+        assert_eq!(rules(r#"<a href="/x"> </a>"#), [Rule::LinkText]);
+        assert_eq!(
+            rules(r#"<p id="a"></p><p id="a"></p>"#),
+            [Rule::DuplicateId]
+        );
+        assert_eq!(rules(r#"<input name="q">"#), [Rule::FormLabel]);
+    }
+
+    #[test]
+    fn test_accessible_markup_passes() {
+        let html = r#"<html lang="en"><h1>A</h1><h2>B</h2>
+            <img src="deco.png" alt="">
+            <a href="/x"><img src="home.png" alt="Home"></a>
+            <a href="/y" aria-label="Search"></a>
+            <label>Query <input name="q"></label>
+            <input id="e" name="email"><label for="e">Email</label>
+            <input type="hidden" name="token"><input type="submit">
+            </html>"#;
+
+        assert_eq!(rules(html), []);
+    }
+
+    #[test]
+    fn test_severities_come_from_config() {
+        let page = RenderedPage {
+            slug: "posts/hello".to_string(),
+            html: r#"<html><img src="a.png"></html>"#.to_string(),
+            source: PathBuf::from("content/posts/hello.md"),
+        };
+        let config = A11yConfig {
+            img_alt: Severity::Error,
+            html_lang: Severity::Off,
+            ..Default::default()
+        };
+
+        let findings = lint_pages(&[page], &config);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, Rule::ImgAlt);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(
+            findings[0].to_string(),
+            "[img-alt] posts/hello/index.html (content/posts/hello.md): <img src=\"a.png\"> has no alt text"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::engine::parser;
+use crate::engine::render::markdown_options;
+use crate::models::Post;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const POSTS_FILE: &str = "posts.json";
+
+/// Parsed posts and their rendered Markdown from the previous build, kept in
+/// `.ferropress-cache/`. An entry is reused only when the file's contents, the
+/// FerroPress version and the Markdown options are all unchanged.
+pub struct PostCache {
+    file: PathBuf,
+    fingerprint: String,
+    previous: BTreeMap<String, CachedPost>,
+    /// Entries used by this build; saving only these drops deleted posts
+    current: Mutex<BTreeMap<String, CachedPost>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    fingerprint: String,
+    posts: BTreeMap<String, CachedPost>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedPost {
+    /// SHA-256 of the Markdown file
+    hash: String,
+    post: Post,
+    html: String,
+}
+
+impl PostCache {
+    /// Read the cache in `dir`. A missing, corrupt or outdated cache is treated as empty.
+    pub fn load(dir: &Path) -> Self {
+        let file = dir.join(POSTS_FILE);
+        let fingerprint = fingerprint();
+
+        let previous = fs::read(&file)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|cached| cached.fingerprint == fingerprint)
+            .map(|cached| cached.posts)
+            .unwrap_or_default();
+
+        Self {
+            file,
+            fingerprint,
+            previous,
+            current: Mutex::default(),
+        }
+    }
+
+    /// [`parser::parse_post`], skipping front matter parsing and Markdown
+    /// conversion when the file hasn't changed since the last build.
+    pub fn parse_post(&self, path: &Path, prefix: &Path) -> Result<Post> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file at {:?}", path))?;
+        let key = path
+            .strip_prefix(prefix)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let hash = format!("{:x}", Sha256::digest(raw.as_bytes()));
+
+        let entry = match self.previous.get(&key) {
+            Some(cached) if cached.hash == hash => cached.clone(),
+            _ => {
+                let post = parser::parse_post_source(path, prefix, &raw)?;
+                CachedPost {
+                    hash,
+                    html: post.html.clone(),
+                    post,
+                }
+            }
+        };
+
+        let mut post = entry.post.clone();
+        post.html = entry.html.clone();
+        post.source = path.to_path_buf();
+
+        self.current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, entry);
+        Ok(post)
+    }
+
+    /// Write the entries used by this build back to disk.
+    pub fn save(self) -> Result<()> {
+        let cache = CacheFile {
+            fingerprint: self.fingerprint,
+            posts: self.current.into_inner().unwrap_or_else(|e| e.into_inner()),
+        };
+
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory {:?}", dir))?;
+        }
+        let json = serde_json::to_vec(&cache).context("Failed to serialize build cache")?;
+        fs::write(&self.file, json)
+            .with_context(|| format!("Failed to write build cache {:?}", self.file))
+    }
+}
+
+/// Anything besides the file contents that changes how a post is parsed or rendered.
+fn fingerprint() -> String {
+    format!(
+        "ferropress {}; markdown {:x}",
+        env!("CARGO_PKG_VERSION"),
+        markdown_options().bits()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_reuses_unchanged_posts_and_reparses_edits() {
+        let dir = tempdir().unwrap();
+        let content = dir.path().join("content");
+        let cache_dir = dir.path().join(".ferropress-cache");
+        fs::create_dir_all(content.join("posts")).unwrap();
+        let post_path = content.join("posts/hello.md");
+        fs::write(&post_path, "---\ntitle: Hello\ndate: 2026-01-01\n---\n*Hi*").unwrap();
+
+        let cache = PostCache::load(&cache_dir);
+        assert_eq!(
+            cache.parse_post(&post_path, &content).unwrap().html,
+            "<p><em>Hi</em></p>\n"
+        );
+        cache.save().unwrap();
+
+        // doctor the stored HTML so a cache hit is observable
+        let file = cache_dir.join(POSTS_FILE);
+        let stored = fs::read_to_string(&file).unwrap();
+        fs::write(&file, stored.replace("<em>Hi</em>", "cached")).unwrap();
+
+        let cache = PostCache::load(&cache_dir);
+        let post = cache.parse_post(&post_path, &content).unwrap();
+        assert_eq!(post.html, "<p>cached</p>\n");
+        assert_eq!(post.source, post_path);
+
+        fs::write(
+            &post_path,
+            "---\ntitle: Hello\ndate: 2026-01-01\n---\n*Bye*",
+        )
+        .unwrap();
+        let post = cache.parse_post(&post_path, &content).unwrap();
+        assert_eq!(post.html, "<p><em>Bye</em></p>\n");
+    }
+}
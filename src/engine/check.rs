@@ -0,0 +1,220 @@
+use crate::engine::fingerprint::AssetManifest;
+use crate::engine::incremental::DependencyGraph;
+use crate::engine::render::{self, Renderer};
+use crate::engine::{self, a11y, collections, data, links, manifest, parser, theme};
+use crate::models::{BuildOptions, ProjectPaths, RenderedPage, Severity};
+use anyhow::Context;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Outcome of running the whole pipeline in memory.
+#[derive(Default)]
+pub struct CheckReport {
+    /// Pages that rendered successfully
+    pub pages: Vec<RenderedPage>,
+    pub errors: Vec<anyhow::Error>,
+    /// Problems that don't fail the check
+    pub warnings: Vec<String>,
+}
+
+/// Load config, parse content, compile templates and render every page without
+/// touching dist/. Stages keep going after a failure where the next stage can
+/// still run, so a single pass reports as many problems as possible.
+pub fn check_site(paths: &ProjectPaths, options: &BuildOptions) -> CheckReport {
+    let mut report = CheckReport::default();
+
+    // Nothing else can be checked without site.toml
+    let mut config = match theme::load_site_config(paths).context("Failed to load site.toml") {
+        Ok(config) => config,
+        Err(e) => {
+            report.errors.push(e);
+            return report;
+        }
+    };
+
+    match data::load_data_dir(&paths.data).context("Failed to load data files") {
+        Ok(data) => {
+            config.data = data;
+            match collections::resolve_collections(&config).context("Failed to resolve collections")
+            {
+                Ok(entries) => config.collection_entries = entries,
+                Err(e) => report.errors.push(e),
+            }
+        }
+        // collections depend on data, so skip them rather than report knock-on errors
+        Err(e) => report.errors.push(e),
+    }
+
+    let mut posts = match parser::parse_posts_collecting_errors(&paths.content, None) {
+        Ok((posts, errors)) => {
+            report.errors.extend(errors);
+            posts
+        }
+        Err(e) => {
+            report.errors.push(e);
+            Vec::new()
+        }
+    };
+    posts.retain(|post| options.includes(post));
+
+    let template_dirs = theme::template_dirs(paths, &config);
+    let templates = match theme::layered_files(&template_dirs) {
+        Ok(templates) => templates,
+        Err(e) => {
+            report.errors.push(e);
+            return report;
+        }
+    };
+    let graph = match DependencyGraph::new(&templates) {
+        Ok(graph) => graph,
+        Err(e) => {
+            report.errors.push(e);
+            return report;
+        }
+    };
+
+    // Leave out templates that don't parse and everything built on them, so the
+    // rest of the site still renders instead of failing on knock-on errors
+    let syntax_errors = render::template_syntax_errors(&templates);
+    let broken: BTreeSet<String> = syntax_errors.keys().cloned().collect();
+    report.errors.extend(syntax_errors.into_values());
+    let (skipped, usable): (BTreeMap<_, _>, BTreeMap<_, _>) = templates
+        .into_iter()
+        .partition(|(name, _)| graph.includes_any(name, &broken));
+
+    let mut renderer = match Renderer::from_templates(usable, AssetManifest::default()) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            report.errors.push(e);
+            return report;
+        }
+    };
+    renderer.register_site_functions(&posts, &config);
+
+    // Pages that weren't rendered would show up as broken link targets
+    let mut render_failed = !skipped.is_empty();
+    let results = renderer.render_where(&posts, &config, |page| {
+        !skipped.contains_key(page.template())
+    });
+    for result in results {
+        match result {
+            Ok(page) => report.pages.push(page),
+            Err(e) => {
+                render_failed = true;
+                report.errors.push(e);
+            }
+        }
+    }
+
+    report
+        .errors
+        .extend(engine::duplicate_outputs(&report.pages));
+
+    if !render_failed {
+        let slugs = report.pages.iter().map(|page| page.slug.as_str());
+        match manifest::output_files(paths, &config, slugs) {
+            Ok(files) => report.errors.extend(
+                links::check_links(&report.pages, &files, &config)
+                    .into_iter()
+                    .map(|link| anyhow::anyhow!("Broken link in {}", link)),
+            ),
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    if options.a11y {
+        for finding in a11y::lint_pages(&report.pages, &config.a11y) {
+            match finding.severity {
+                Severity::Error => report.errors.push(anyhow::anyhow!("{}", finding)),
+                _ => report.warnings.push(finding.to_string()),
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_one_pass_reports_errors_from_every_stage_without_writing_dist() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "site.toml",
+            "title = \"T\"\nauthor = \"A\"\nfooter_text = \"\"\n",
+        );
+        write(
+            root,
+            "content/posts/good.md",
+            "---\ntitle: Good\ndate: 2025-01-01\n---\nHi\n",
+        );
+        write(
+            root,
+            "content/posts/bad.md",
+            "---\ntitle: Bad\n---\nNo date\n",
+        );
+        write(root, "templates/post.html", "{{ content | safe }}");
+        write(root, "templates/layouts/broken.html", "{{ title | }}");
+        write(
+            root,
+            "templates/pages/about.html",
+            r#"{% extends "layouts/broken.html" %}"#,
+        );
+        write(root, "templates/pages/index.html", "{{ missing }}");
+        write(root, "templates/pages/ok.html", "{{ posts | length }}");
+        let paths = ProjectPaths::from_root(root);
+
+        let report = check_site(&paths, &BuildOptions::default());
+
+        let errors: Vec<String> = report.errors.iter().map(|e| format!("{:#}", e)).collect();
+        assert_eq!(errors.len(), 3, "{errors:#?}");
+        let reported = |file: &str| errors.iter().any(|e| e.contains(file));
+        assert!(reported("bad.md"), "{errors:#?}");
+        assert!(reported("broken.html"), "{errors:#?}");
+        assert!(reported("index.html"), "{errors:#?}");
+        // pages built on the broken layout are skipped, not reported again
+        assert!(!reported("about.html"), "{errors:#?}");
+
+        let mut slugs: Vec<&str> = report.pages.iter().map(|p| p.slug.as_str()).collect();
+        slugs.sort();
+        assert_eq!(slugs, ["ok", "posts/good"]);
+        assert!(!paths.dist.exists());
+    }
+
+    #[test]
+    fn test_a_valid_site_checks_clean() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "site.toml",
+            "title = \"T\"\nauthor = \"A\"\nfooter_text = \"\"\n",
+        );
+        write(
+            root,
+            "content/posts/good.md",
+            "---\ntitle: Good\ndate: 2025-01-01\n---\n[home](/)\n",
+        );
+        write(root, "templates/post.html", "{{ content | safe }}");
+        write(root, "templates/pages/index.html", "{{ posts | length }}");
+        let paths = ProjectPaths::from_root(root);
+
+        let report = check_site(&paths, &BuildOptions::default());
+
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.pages.len(), 2);
+        assert!(!paths.dist.exists());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::engine::utils;
+use crate::models::{CollectionEntry, SiteConfig};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+/// Pages every site has at the root of dist/, which a collection with
+/// `path = ""` must not overwrite.
+const RESERVED_SLUGS: [&str; 2] = ["index", "404"];
+
+/// Expand every `[[collections]]` entry into one page per data record.
+/// Expects `config.data` to be loaded already. Slugs must be unique across all
+/// collections; clashes with posts and pages are caught once those are known.
+pub fn resolve_collections(config: &SiteConfig) -> Result<BTreeMap<String, Vec<CollectionEntry>>> {
+    let mut resolved = BTreeMap::new();
+    // slug -> data file of the collection that produced it
+    let mut taken: BTreeMap<String, &str> = BTreeMap::new();
+
+    for collection in &config.collections {
+        let name = collection.name();
+        let records = config
+            .data
+            .get(&name)
+            .with_context(|| {
+                format!(
+                    "Collection data file '{}' not found in data/",
+                    collection.data
+                )
+            })?
+            .as_array()
+            .with_context(|| {
+                format!(
+                    "Collection data file '{}' must contain a list of records",
+                    collection.data
+                )
+            })?;
+
+        let prefix = collection.output_prefix();
+        // the prefix becomes directories under dist/, so it may not climb out of it
+        if !Path::new(&prefix)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            anyhow::bail!(
+                "Collection '{}' has path '{}', which would write outside dist/ (use plain directory names)",
+                collection.data,
+                collection.path.as_deref().unwrap_or_default()
+            );
+        }
+        let mut entries = Vec::with_capacity(records.len());
+
+        for (i, record) in records.iter().enumerate() {
+            let raw = match record.get(&collection.slug_field) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                _ => anyhow::bail!(
+                    "Record {} in '{}' has no usable '{}' field for its slug",
+                    i + 1,
+                    collection.data,
+                    collection.slug_field
+                ),
+            };
+
+            let slug_part = utils::Slugify::from_text(&raw);
+            if slug_part.is_empty() {
+                anyhow::bail!(
+                    "Record {} in '{}' has an empty slug '{}'",
+                    i + 1,
+                    collection.data,
+                    raw
+                );
+            }
+
+            let slug = if prefix.is_empty() {
+                slug_part
+            } else {
+                format!("{}/{}", prefix, slug_part)
+            };
+
+            if RESERVED_SLUGS.contains(&slug.as_str()) {
+                anyhow::bail!(
+                    "Record {} in '{}' has slug '{}', which would overwrite the site's own {} page",
+                    i + 1,
+                    collection.data,
+                    slug,
+                    slug
+                );
+            }
+            if let Some(other) = taken.insert(slug.clone(), &collection.data) {
+                anyhow::bail!(
+                    "Record {} in '{}' has slug '{}', which '{}' already uses",
+                    i + 1,
+                    collection.data,
+                    slug,
+                    other
+                );
+            }
+
+            entries.push(CollectionEntry {
+                slug,
+                template: collection.template.clone(),
+                record: record.clone(),
+            });
+        }
+
+        resolved.insert(name, entries);
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CollectionConfig;
+    use serde_json::json;
+
+    fn config(collections: &[(&str, Option<&str>)]) -> SiteConfig {
+        let mut config = SiteConfig::default();
+        config.data.insert(
+            "products".to_string(),
+            json!([{ "sku": "a1" }, { "sku": "index" }]),
+        );
+        config
+            .data
+            .insert("parts".to_string(), json!([{ "sku": "a1" }]));
+        config.collections = collections
+            .iter()
+            .map(|(data, path)| CollectionConfig {
+                data: data.to_string(),
+                slug_field: "sku".to_string(),
+                template: "product.html".to_string(),
+                path: path.map(String::from),
+            })
+            .collect();
+        config
+    }
+
+    #[test]
+    fn test_rejects_slugs_shared_between_collections() {
+        let resolved = resolve_collections(&config(&[("products.json", None)])).unwrap();
+        let slugs: Vec<_> = resolved["products"].iter().map(|e| &e.slug).collect();
+        assert_eq!(slugs, ["products/a1", "products/index"]);
+
+        let err = resolve_collections(&config(&[
+            ("products.json", Some("shop")),
+            ("parts.json", Some("shop")),
+        ]))
+        .unwrap_err();
+        assert!(err.to_string().contains("'shop/a1'"), "{err}");
+        assert!(
+            err.to_string().contains("'products.json' already uses"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_paths_outside_dist() {
+        for path in ["../x", "shop/../../x", "./shop"] {
+            let err = resolve_collections(&config(&[("products.json", Some(path))])).unwrap_err();
+            assert!(err.to_string().contains("write outside dist/"), "{err}");
+        }
+        assert!(resolve_collections(&config(&[("products.json", Some("/shop/items/"))])).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_root_slugs_of_site_pages() {
+        let err = resolve_collections(&config(&[("products.json", Some(""))])).unwrap_err();
+        assert!(err.to_string().contains("slug 'index'"), "{err}");
+    }
+}
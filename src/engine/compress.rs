@@ -0,0 +1,213 @@
+use crate::engine::manifest;
+use crate::models::ProjectPaths;
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Text formats worth compressing; images and fonts are compressed already.
+const EXTENSIONS: [&str; 6] = ["html", "css", "js", "mjs", "xml", "json"];
+
+/// Below this, a compressed variant saves less than the headers around it.
+const MIN_SIZE: usize = 1024;
+
+type Encoder = fn(&[u8]) -> Result<Vec<u8>>;
+
+/// Variants written next to each file, as `<file>.gz` and `<file>.br`.
+const ENCODINGS: [(&str, Encoder); 2] = [("gz", gzip), ("br", brotli)];
+
+/// Kept in `.ferropress-cache/`: what the last build compressed, so unchanged
+/// files aren't compressed again.
+const CACHE_FILE: &str = "compressed.json";
+
+/// The variants written for one file, relative to dist/.
+#[derive(Clone, Serialize, Deserialize)]
+struct Compressed {
+    /// SHA-256 of the contents the variants were made from
+    hash: String,
+    variants: Vec<String>,
+}
+
+/// Write precompressed siblings of every text file in `outputs` (relative to
+/// dist/) of at least [`MIN_SIZE`] bytes. Files whose contents are unchanged since
+/// the last build keep their variants, and variants that wouldn't be smaller are
+/// skipped. Returns the compressed files, relative to dist/.
+pub fn compress_outputs(paths: &ProjectPaths, outputs: &BTreeSet<String>) -> Result<Vec<String>> {
+    let cache_file = paths.cache.join(CACHE_FILE);
+    let previous: BTreeMap<String, Compressed> = fs::read(&cache_file)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+
+    let compressed = outputs
+        .par_iter()
+        .filter(|relative| is_compressible(relative))
+        .map(|relative| {
+            let entry = compress_file(&paths.dist, relative, previous.get(relative))?;
+            Ok(entry.map(|entry| (relative.clone(), entry)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let current: BTreeMap<String, Compressed> = compressed.into_iter().flatten().collect();
+
+    fs::create_dir_all(&paths.cache)
+        .with_context(|| format!("Failed to create cache directory {:?}", paths.cache))?;
+    let json = serde_json::to_vec(&current).context("Failed to serialize compression cache")?;
+    fs::write(&cache_file, json)
+        .with_context(|| format!("Failed to write compression cache {:?}", cache_file))?;
+
+    Ok(current
+        .into_values()
+        .flat_map(|entry| entry.variants)
+        .collect())
+}
+
+fn is_compressible(relative: &str) -> bool {
+    relative
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| EXTENSIONS.contains(&ext))
+}
+
+fn compress_file(
+    dist: &Path,
+    relative: &str,
+    previous: Option<&Compressed>,
+) -> Result<Option<Compressed>> {
+    let source = dist.join(relative);
+    let contents = fs::read(&source).with_context(|| format!("Failed to read {:?}", source))?;
+    if contents.len() < MIN_SIZE {
+        return Ok(None);
+    }
+
+    let hash = format!("{:x}", Sha256::digest(&contents));
+    if let Some(previous) = previous
+        && previous.hash == hash
+        && previous
+            .variants
+            .iter()
+            .all(|name| dist.join(name).is_file())
+    {
+        return Ok(Some(previous.clone()));
+    }
+
+    let mut variants = Vec::new();
+    for (ext, encode) in ENCODINGS {
+        let encoded =
+            encode(&contents).with_context(|| format!("Failed to compress {:?}", source))?;
+        if encoded.len() < contents.len() {
+            let name = format!("{}.{}", relative, ext);
+            manifest::write_if_changed(&dist.join(&name), &encoded)?;
+            variants.push(name);
+        }
+    }
+    Ok(Some(Compressed { hash, variants }))
+}
+
+fn gzip(contents: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(contents)?;
+    Ok(encoder.finish()?)
+}
+
+fn brotli(contents: &[u8]) -> Result<Vec<u8>> {
+    // maximum quality with the largest standard window (4 MiB)
+    let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+    encoder.write_all(contents)?;
+    Ok(encoder.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use std::time::{Duration, SystemTime};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_compresses_large_text_outputs_only() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        fs::create_dir_all(&paths.dist).unwrap();
+        let page = "<p>Hello, world!</p>\n".repeat(100);
+        fs::write(paths.dist.join("index.html"), &page).unwrap();
+        fs::write(
+            paths.dist.join("app.mjs"),
+            "export const x = 1;\n".repeat(100),
+        )
+        .unwrap();
+        fs::write(paths.dist.join("small.css"), "body{}").unwrap();
+        fs::write(paths.dist.join("photo.png"), page.as_bytes()).unwrap();
+        let outputs = ["index.html", "app.mjs", "small.css", "photo.png"]
+            .map(String::from)
+            .into();
+
+        let compressed = compress_outputs(&paths, &outputs).unwrap();
+        assert_eq!(
+            compressed,
+            ["app.mjs.gz", "app.mjs.br", "index.html.gz", "index.html.br"]
+        );
+
+        let mut decoded = String::new();
+        GzDecoder::new(fs::File::open(paths.dist.join("index.html.gz")).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, page);
+
+        let mut decoded = String::new();
+        brotli::Decompressor::new(
+            fs::File::open(paths.dist.join("index.html.br")).unwrap(),
+            4096,
+        )
+        .read_to_string(&mut decoded)
+        .unwrap();
+        assert_eq!(decoded, page);
+    }
+
+    #[test]
+    fn test_recompresses_by_content_not_mtime() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        fs::create_dir_all(&paths.dist).unwrap();
+        let page = paths.dist.join("index.html");
+        let variant = paths.dist.join("index.html.gz");
+        let outputs = BTreeSet::from(["index.html".to_string()]);
+        let decode = || {
+            let mut decoded = String::new();
+            GzDecoder::new(fs::File::open(&variant).unwrap())
+                .read_to_string(&mut decoded)
+                .unwrap();
+            decoded
+        };
+
+        fs::write(&page, "a".repeat(2000)).unwrap();
+        compress_outputs(&paths, &outputs).unwrap();
+
+        // unchanged contents keep the variant as it is
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        fs::File::options()
+            .write(true)
+            .open(&variant)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        compress_outputs(&paths, &outputs).unwrap();
+        assert_eq!(fs::metadata(&variant).unwrap().modified().unwrap(), old);
+
+        // new contents are compressed again, even with an older mtime than the variant
+        fs::write(&page, "b".repeat(2000)).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&page)
+            .unwrap()
+            .set_modified(old - Duration::from_secs(60))
+            .unwrap();
+        compress_outputs(&paths, &outputs).unwrap();
+        assert_eq!(decode(), "b".repeat(2000));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::engine::html::{self, Token};
+use crate::models::{CspConfig, CspMode, RenderedPage};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+
+/// Written to the root of dist/ in `headers` mode.
+pub const HEADERS_FILE: &str = "_headers";
+
+/// Same-origin only, no plugins, and no foreign `<base>` or form targets.
+const BASE_POLICY: [(&str, &str); 10] = [
+    ("default-src", "'self'"),
+    ("script-src", "'self'"),
+    ("style-src", "'self'"),
+    ("img-src", "'self' data:"),
+    ("font-src", "'self'"),
+    ("connect-src", "'self'"),
+    ("object-src", "'none'"),
+    ("base-uri", "'self'"),
+    ("form-action", "'self'"),
+    // ignored (with a console warning) when delivered in a <meta> tag
+    ("frame-ancestors", "'none'"),
+];
+
+/// `'sha256-…'` sources for the inline code a page needs.
+#[derive(Default)]
+struct InlineHashes {
+    scripts: BTreeSet<String>,
+    styles: BTreeSet<String>,
+    /// `onclick="…"` and other event handler attributes
+    script_attrs: BTreeSet<String>,
+    /// `style="…"` attributes
+    style_attrs: BTreeSet<String>,
+}
+
+/// Add the configured policy to production pages. In `meta` mode each page gets
+/// its own tag; in `headers` mode the site-wide `_headers` file contents are
+/// returned for the caller to write.
+pub fn apply(pages: &mut [RenderedPage], config: &CspConfig) -> Option<String> {
+    match config.mode {
+        CspMode::Off => None,
+        CspMode::Meta => {
+            pages.par_iter_mut().for_each(|page| {
+                let mut hashes = InlineHashes::default();
+                collect_hashes(&page.html, &mut hashes);
+                let policy = policy(config, &hashes, true);
+                page.html = insert_meta(&page.html, &policy);
+            });
+            None
+        }
+        CspMode::Headers => {
+            let mut hashes = InlineHashes::default();
+            for page in pages.iter() {
+                collect_hashes(&page.html, &mut hashes);
+            }
+            Some(format!(
+                "/*\n  Content-Security-Policy: {}\n",
+                policy(config, &hashes, false)
+            ))
+        }
+    }
+}
+
+/// Files [`apply`] makes the build write, relative to dist/.
+pub fn outputs(config: &CspConfig) -> Vec<String> {
+    match config.mode {
+        CspMode::Headers => vec![HEADERS_FILE.to_string()],
+        CspMode::Off | CspMode::Meta => Vec::new(),
+    }
+}
+
+fn collect_hashes(page: &str, hashes: &mut InlineHashes) {
+    let tokens = html::tokenize(page);
+    // which inline element the next raw text belongs to
+    let mut inline: Option<&str> = None;
+
+    for token in &tokens {
+        match token {
+            Token::Start(tag) => {
+                for (name, value) in &tag.attrs {
+                    if name == "style" && !value.trim().is_empty() {
+                        hashes.style_attrs.insert(hash_source(value));
+                    } else if name.starts_with("on") && !value.trim().is_empty() {
+                        hashes.script_attrs.insert(hash_source(value));
+                    }
+                }
+                inline = match tag.name.as_str() {
+                    "script" if !tag.has_attr("src") => Some("script"),
+                    "style" => Some("style"),
+                    _ => None,
+                };
+            }
+            Token::RawText(code) => match inline.take() {
+                Some("script") => {
+                    hashes.scripts.insert(hash_source(code));
+                }
+                Some(_) => {
+                    hashes.styles.insert(hash_source(code));
+                }
+                None => {}
+            },
+            _ => inline = None,
+        }
+    }
+}
+
+fn hash_source(code: &str) -> String {
+    format!(
+        "'sha256-{}'",
+        STANDARD.encode(Sha256::digest(code.as_bytes()))
+    )
+}
+
+fn policy(config: &CspConfig, hashes: &InlineHashes, for_meta: bool) -> String {
+    let mut directives: Vec<(String, Vec<String>)> = BASE_POLICY
+        .iter()
+        .filter(|(name, _)| !(for_meta && *name == "frame-ancestors"))
+        .map(|(name, sources)| {
+            let sources = sources.split(' ').map(String::from).collect();
+            (name.to_string(), sources)
+        })
+        .collect();
+
+    for (directive, inline, attrs) in [
+        ("script-src", &hashes.scripts, &hashes.script_attrs),
+        ("style-src", &hashes.styles, &hashes.style_attrs),
+    ] {
+        add_sources(&mut directives, directive, inline.iter().cloned());
+        if !attrs.is_empty() {
+            // hashes only cover attributes when 'unsafe-hashes' is present
+            let sources = std::iter::once("'unsafe-hashes'".to_string());
+            add_sources(
+                &mut directives,
+                directive,
+                sources.chain(attrs.iter().cloned()),
+            );
+        }
+    }
+    for (directive, sources) in &config.directives {
+        add_sources(&mut directives, directive, sources.iter().cloned());
+    }
+
+    directives
+        .iter()
+        .map(|(name, sources)| format!("{} {}", name, sources.join(" ")))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Append `sources` to `directive` (adding it if missing), skipping duplicates.
+fn add_sources(
+    directives: &mut Vec<(String, Vec<String>)>,
+    directive: &str,
+    sources: impl IntoIterator<Item = String>,
+) {
+    let index = match directives.iter().position(|(name, _)| name == directive) {
+        Some(i) => i,
+        None => {
+            directives.push((directive.to_string(), Vec::new()));
+            directives.len() - 1
+        }
+    };
+    for source in sources {
+        if !directives[index].1.contains(&source) {
+            directives[index].1.push(source);
+        }
+    }
+}
+
+/// Put the policy first thing in `<head>`, so it covers every element after it.
+fn insert_meta(page: &str, policy: &str) -> String {
+    let tokens = html::tokenize(page);
+    let Some(head) = html::start_tags(&tokens).find(|tag| tag.name == "head") else {
+        return page.to_string();
+    };
+
+    let policy = policy.replace('&', "&amp;").replace('"', "&quot;");
+    let at = head.span.end;
+    format!(
+        "{}<meta http-equiv=\"Content-Security-Policy\" content=\"{}\">{}",
+        &page[..at],
+        policy,
+        &page[at..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_meta_policy_hashes_inline_code() {
+        let config = CspConfig {
+            mode: CspMode::Meta,
+            directives: BTreeMap::from([("img-src".to_string(), vec!["https://cdn".to_string()])]),
+        };
+        let mut pages = vec![RenderedPage {
+            slug: "index".to_string(),
+            html: "<html><head><script>go()</script><script src=\"/a.js\"></script></head>\
+                <body><p style=\"color: red\">Hi</p></body></html>"
+                .to_string(),
+            source: "templates/pages/index.html".into(),
+        }];
+
+        assert_eq!(apply(&mut pages, &config), None);
+        let html = &pages[0].html;
+        assert!(html.starts_with("<html><head><meta http-equiv=\"Content-Security-Policy\""));
+        assert!(html.contains(&format!("script-src 'self' {};", hash_source("go()"))));
+        assert!(html.contains(&format!(
+            "style-src 'self' 'unsafe-hashes' {};",
+            hash_source("color: red")
+        )));
+        assert!(html.contains("img-src 'self' data: https://cdn;"));
+        assert!(!html.contains("frame-ancestors"));
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use gray_matter::engine::Engine;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DATA_EXTENSIONS: [&str; 5] = ["toml", "yaml", "yml", "json", "csv"];
+
+/// Load every `data/*.{toml,yaml,yml,json,csv}` file into a map keyed by file stem.
+/// e.g. `data/team.yaml` -> `data.team`
+pub fn load_data_dir(data_dir: &Path) -> Result<Map<String, Value>> {
+    let mut data = Map::new();
+
+    if !data_dir.exists() {
+        return Ok(data);
+    }
+
+    for path in data_files(data_dir)? {
+        let key = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .with_context(|| format!("Invalid data file name {:?}", path))?;
+
+        if data.contains_key(&key) {
+            anyhow::bail!(
+                "Duplicate data key '{}': {:?} clashes with another file of the same name",
+                key,
+                path
+            );
+        }
+
+        let value = load_data_file(&path)?;
+        data.insert(key, value);
+    }
+
+    Ok(data)
+}
+
+/// Top-level data files with a supported extension, sorted for stable output.
+pub fn data_files(data_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(data_dir)
+        .with_context(|| format!("Failed to read data directory {:?}", data_dir))?
+    {
+        let path = entry?.path();
+        if path.is_file() && is_data_file(&path) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+pub fn is_data_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            DATA_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+pub fn load_data_file(path: &Path) -> Result<Value> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read data file {:?}", path))?;
+
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    // Each parser's error already carries the line/column, so we only add the file.
+    let parsed = match ext.as_str() {
+        "toml" => toml::from_str::<Value>(&raw).map_err(anyhow::Error::from),
+        "json" => serde_json::from_str::<Value>(&raw).map_err(anyhow::Error::from),
+        "yaml" | "yml" => parse_yaml(&raw),
+        "csv" => parse_csv(&raw),
+        other => Err(anyhow::anyhow!("Unsupported data format '{}'", other)),
+    };
+
+    parsed.with_context(|| format!("Failed to parse data file {:?}", path))
+}
+
+fn parse_yaml(raw: &str) -> Result<Value> {
+    let pod = gray_matter::engine::YAML::parse(raw).map_err(|e| anyhow::anyhow!("{}", e))?;
+    pod.deserialize::<Value>()
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// CSV files become an array of objects keyed by the header row.
+fn parse_csv(raw: &str) -> Result<Value> {
+    let mut reader = csv::Reader::from_reader(raw.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let row: Map<String, Value> = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(h, v)| (h.to_string(), Value::String(v.to_string())))
+            .collect();
+        rows.push(Value::Object(row));
+    }
+
+    Ok(Value::Array(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_data_dir_keys_by_stem() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("team.yaml"), "- name: Ada\n- name: Linus\n").unwrap();
+        fs::write(dir.path().join("site.toml"), "motto = \"hot iron\"\n").unwrap();
+        fs::write(dir.path().join("talks.json"), r#"[{"title": "Forging"}]"#).unwrap();
+        fs::write(dir.path().join("products.csv"), "sku,name\na1,Anvil\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let data = load_data_dir(dir.path()).unwrap();
+
+        assert_eq!(data.len(), 4);
+        assert_eq!(data["team"][1]["name"], "Linus");
+        assert_eq!(data["site"]["motto"], "hot iron");
+        assert_eq!(data["talks"][0]["title"], "Forging");
+        assert_eq!(data["products"][0]["name"], "Anvil");
+    }
+
+    #[test]
+    fn test_parse_error_names_file_and_line() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("broken.json"), "{\n  \"a\": 1,\n  oops\n}").unwrap();
+
+        let err = format!("{:#}", load_data_dir(dir.path()).unwrap_err());
+
+        assert!(err.contains("broken.json"), "{err}");
+        assert!(err.contains("line 3"), "{err}");
+    }
+
+    #[test]
+    fn test_yaml_and_csv_errors_name_file_and_line() {
+        let dir = tempdir().unwrap();
+        let yaml = dir.path().join("team.yaml");
+        fs::write(&yaml, "- name: Ada\n- name: Linus\n  role: : lead\n").unwrap();
+        let csv = dir.path().join("products.csv");
+        fs::write(&csv, "sku,name\na1,Anvil\na2\n").unwrap();
+
+        let err = format!("{:#}", load_data_file(&yaml).unwrap_err());
+        assert!(err.contains("team.yaml"), "{err}");
+        assert!(err.contains("line 3"), "{err}");
+
+        let err = format!("{:#}", load_data_file(&csv).unwrap_err());
+        assert!(err.contains("products.csv"), "{err}");
+        assert!(err.contains("line: 3"), "{err}");
+    }
+}
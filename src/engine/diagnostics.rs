@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// An error pinned to a spot in a source file, displayed compiler-style:
+///
+/// ```text
+/// invalid date '2025-13-01', expected YYYY-MM-DD
+///   --> content/posts/hello.md:3:7
+///    |
+///  3 | date: 2025-13-01
+///    |       ^
+/// ```
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub message: String,
+    pub file: PathBuf,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in characters
+    pub column: usize,
+    /// The offending source line
+    pub snippet: String,
+}
+
+impl Diagnostic {
+    pub fn new(
+        message: impl Into<String>,
+        file: &Path,
+        source: &str,
+        line: usize,
+        column: usize,
+    ) -> Self {
+        let line = line.max(1);
+        Self {
+            message: message.into(),
+            file: file.to_path_buf(),
+            line,
+            column: column.max(1),
+            snippet: source.lines().nth(line - 1).unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let caret_pad: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(
+            f,
+            "{} --> {}:{}:{}",
+            gutter,
+            self.file.display(),
+            self.line,
+            self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(f, "{} | {}^", gutter, caret_pad)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Pull `(line, column)` out of messages like "... line 2 column 7".
+pub fn line_and_column(message: &str) -> Option<(usize, usize)> {
+    let after_line = &message[message.rfind("line ")? + 5..];
+    let line: usize = after_line
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    let after_column = &after_line[after_line.find("column ")? + 7..];
+    let column: usize = after_column
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    Some((line, column))
+}
+
+/// 1-based line and column where the value of the top-level YAML `key` starts,
+/// searching the front matter between the opening and closing `---`.
+pub fn locate_front_matter_key(source: &str, key: &str) -> Option<(usize, usize)> {
+    for (i, line) in source.lines().enumerate().skip(1) {
+        if line.trim_end() == "---" {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix(key)
+            && let Some(value) = rest.trim_start().strip_prefix(':')
+        {
+            let value_start = line.len() - value.trim_start().len();
+            return Some((i + 1, line[..value_start].chars().count() + 1));
+        }
+    }
+    None
+}
+
+/// Turn a Tera error into a diagnostic pointing into the template that caused it.
+/// The template is the innermost one the error chain names. Syntax errors carry
+/// their own position; for render errors (undefined variables, failing filters and
+/// functions) the first use of the culprit in that template is located instead.
+/// Returns `None` rather than guess when either is unknown.
+pub fn from_tera(error: &tera::Error, templates: &BTreeMap<String, PathBuf>) -> Option<Diagnostic> {
+    let mut chain = Vec::new();
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(error);
+    while let Some(e) = source {
+        chain.push(e);
+        source = e.source();
+    }
+    let messages: Vec<String> = chain.iter().map(|e| e.to_string()).collect();
+
+    let path = named_template(&messages, templates)?;
+    let source = std::fs::read_to_string(path).ok()?;
+
+    // pest syntax errors: " --> 1:7 ... = expected a value"
+    if let Some(pest) = messages.iter().find(|m| m.contains(" --> ")) {
+        let position = &pest[pest.find(" --> ")? + 5..];
+        let position = position.lines().next()?.trim();
+        let (line, column) = position.split_once(':')?;
+        let (line, column) = (line.parse().ok()?, column.parse().ok()?);
+
+        let reason = pest
+            .lines()
+            .rev()
+            .find_map(|l| l.trim().strip_prefix("= "))
+            .unwrap_or("invalid template syntax");
+        return Some(Diagnostic::new(reason, path, &source, line, column));
+    }
+
+    let culprit = chain
+        .iter()
+        .find_map(|e| match &e.downcast_ref::<tera::Error>()?.kind {
+            tera::ErrorKind::CallFilter(name) | tera::ErrorKind::FilterNotFound(name) => {
+                Some((name.clone(), true))
+            }
+            tera::ErrorKind::CallFunction(name) | tera::ErrorKind::FunctionNotFound(name) => {
+                Some((format!("{}(", name), false))
+            }
+            _ => None,
+        });
+    // an undefined variable is a plain message: "Variable `x` not found in context ..."
+    let (needle, is_filter) = culprit.or_else(|| {
+        let rest = messages.last()?.strip_prefix("Variable `")?;
+        Some((rest.split('`').next()?.to_string(), false))
+    })?;
+
+    let (line, column) = find_in_tags(&source, &needle, is_filter)?;
+    let message = messages[messages.len().min(2) - 1..].join(": ");
+    Some(Diagnostic::new(message, path, &source, line, column))
+}
+
+/// [`from_tera`] when a location can be found, otherwise the plain Tera error.
+pub fn tera_error(error: tera::Error, templates: &BTreeMap<String, PathBuf>) -> anyhow::Error {
+    match from_tera(&error, templates) {
+        Some(diagnostic) => diagnostic.into(),
+        None => error.into(),
+    }
+}
+
+/// The innermost template named in the chain, e.g. `while rendering 'post.html'`,
+/// or by path for templates Tera was still loading (`Failed to parse "templates/..."`).
+fn named_template<'a>(
+    messages: &[String],
+    templates: &'a BTreeMap<String, PathBuf>,
+) -> Option<&'a PathBuf> {
+    messages.iter().rev().find_map(|message| {
+        // "Failed to render 'post.html' (error happened in 'base.html')" names the
+        // template it happened in last
+        let by_name = message
+            .split('\'')
+            .skip(1)
+            .step_by(2)
+            .filter_map(|quoted| templates.get(quoted))
+            .last();
+        by_name.or_else(|| {
+            templates
+                .values()
+                .find(|path| message.contains(&format!("{:?}", path)))
+        })
+    })
+}
+
+/// First occurrence of `needle` inside a `{{ }}` or `{% %}` tag; for filters,
+/// only where it follows a `|`.
+fn find_in_tags(source: &str, needle: &str, is_filter: bool) -> Option<(usize, usize)> {
+    for (i, line) in source.lines().enumerate() {
+        let mut from = 0;
+        while let Some(found) = line[from..].find(needle) {
+            let at = from + found;
+            let before = &line[..at];
+            let open = before.rfind("{{").max(before.rfind("{%"));
+            let close = before.rfind("}}").max(before.rfind("%}"));
+            let piped = !is_filter || before.trim_end().ends_with('|');
+            if open.is_some() && open > close && piped {
+                return Some((i + 1, before.chars().count() + 1));
+            }
+            from = at + needle.len();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::render::{self, Renderer};
+    use crate::engine::theme;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn render_error(renderer: &Renderer, name: &str, context: &tera::Context) -> Diagnostic {
+        let error = renderer.tera.render(name, context).unwrap_err();
+        from_tera(&error, &renderer.templates).expect("a located diagnostic")
+    }
+
+    #[test]
+    fn test_syntax_error_points_at_the_broken_template() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.html"), "<p>fine</p>\n").unwrap();
+        fs::write(dir.path().join("post.html"), "<h1>\n{{ title | }}\n</h1>\n").unwrap();
+        let templates = theme::layered_files(&[dir.path().to_path_buf()]).unwrap();
+
+        let error = Renderer::new(&[dir.path().to_path_buf()], Default::default())
+            .err()
+            .unwrap();
+        let diagnostic = error.downcast_ref::<Diagnostic>().unwrap();
+        assert_eq!(diagnostic.file, dir.path().join("post.html"));
+        assert_eq!(diagnostic.line, 2);
+
+        let errors = render::template_syntax_errors(&templates);
+        assert_eq!(errors.len(), 1);
+        let diagnostic = errors["post.html"].downcast_ref::<Diagnostic>().unwrap();
+        assert_eq!(diagnostic.file, dir.path().join("post.html"));
+        assert_eq!(
+            (diagnostic.line, diagnostic.snippet.as_str()),
+            (2, "{{ title | }}")
+        );
+    }
+
+    #[test]
+    fn test_render_errors_point_at_the_culprit_in_the_rendered_template() {
+        let dir = tempdir().unwrap();
+        // sorts first and uses the same names, but is never rendered
+        fs::write(dir.path().join("a.html"), "{{ missing }} {{ 1 | date }}").unwrap();
+        fs::write(
+            dir.path().join("post.html"),
+            "<h1>{{ title }}</h1>\n<p>{{ missing }}</p>\n<time>{{ title | date }}</time>\n",
+        )
+        .unwrap();
+        let renderer = Renderer::new(&[dir.path().to_path_buf()], Default::default()).unwrap();
+        let mut context = tera::Context::new();
+        context.insert("title", "Hello");
+
+        let diagnostic = render_error(&renderer, "post.html", &context);
+        assert_eq!(diagnostic.file, dir.path().join("post.html"));
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 7));
+
+        context.insert("missing", "here");
+        let diagnostic = render_error(&renderer, "post.html", &context);
+        assert_eq!(diagnostic.file, dir.path().join("post.html"));
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 18));
+    }
+
+    #[test]
+    fn test_no_location_when_the_chain_names_no_template() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("post.html"), "{{ missing }}").unwrap();
+        let templates = theme::layered_files(&[dir.path().to_path_buf()]).unwrap();
+
+        let error = tera::Error::msg("Variable `missing` not found in context");
+        assert!(from_tera(&error, &templates).is_none());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::engine::{render, utils};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use tera::{Filter, Tera};
+
+const DEFAULT_DATE_FORMAT: &str = "%B %-d, %Y";
+const DEFAULT_WORDS_PER_MINUTE: u64 = 200;
+
+/// Register FerroPress filters. `date` wraps Tera's built-in rather than replacing it.
+pub fn register(tera: &mut Tera) {
+    tera.register_filter(
+        "date",
+        Date {
+            builtin: Tera::default(),
+        },
+    );
+    tera.register_filter("markdown", Markdown);
+    tera.register_filter("slugify", slugify);
+    tera.register_filter("truncate_words", truncate_words);
+    tera.register_filter("reading_time", reading_time);
+    tera.register_filter("json_encode_pretty", json_encode_pretty);
+}
+
+/// `{{ post.date | date(format="%d %B %Y", locale="fr_FR") }}`
+/// Tera's built-in `date` (timestamps, `YYYY-MM-DD` and RFC 3339 strings,
+/// `timezone=` and `locale=`) with a long `January 3, 2026` default format.
+struct Date {
+    /// Only holds the built-ins, since Tera doesn't expose their functions
+    builtin: Tera,
+}
+
+impl Filter for Date {
+    fn filter(&self, value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+        let mut args = args.clone();
+        args.entry("format".to_string())
+            .or_insert_with(|| json!(DEFAULT_DATE_FORMAT));
+        self.builtin.get_filter("date")?.filter(value, &args)
+    }
+}
+
+/// Render a front matter string as HTML. `inline=true` drops the wrapping `<p>`.
+struct Markdown;
+
+impl Filter for Markdown {
+    fn filter(&self, value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+        let text = value
+            .as_str()
+            .ok_or_else(|| tera::Error::msg("markdown: expected a string"))?;
+
+        let mut html = render::markdown_to_html(text);
+        if args.get("inline").and_then(Value::as_bool).unwrap_or(false) {
+            let trimmed = html.trim_end();
+            if let Some(inner) = trimmed
+                .strip_prefix("<p>")
+                .and_then(|s| s.strip_suffix("</p>"))
+                && !inner.contains("<p>")
+            {
+                html = inner.to_string();
+            }
+        }
+
+        Ok(json!(html))
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+fn slugify(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| tera::Error::msg("slugify: expected a string"))?;
+    Ok(json!(utils::Slugify::from_text(text)))
+}
+
+/// `{{ post.description | truncate_words(count=20, end="…") }}`
+fn truncate_words(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| tera::Error::msg("truncate_words: expected a string"))?;
+
+    let count = args
+        .get("count")
+        .map(|v| {
+            v.as_u64()
+                .ok_or_else(|| tera::Error::msg("truncate_words: `count` must be a number"))
+        })
+        .transpose()?
+        .unwrap_or(50) as usize;
+    let end = optional_str(args, "end")?.unwrap_or("…");
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= count {
+        return Ok(json!(text));
+    }
+
+    Ok(json!(format!("{}{}", words[..count].join(" "), end)))
+}
+
+/// Estimated minutes to read a Markdown or HTML string (never less than 1).
+fn reading_time(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| tera::Error::msg("reading_time: expected a string"))?;
+
+    let wpm = args
+        .get("wpm")
+        .and_then(Value::as_u64)
+        .filter(|wpm| *wpm > 0)
+        .unwrap_or(DEFAULT_WORDS_PER_MINUTE);
+
+    let words = strip_tags(text).split_whitespace().count() as u64;
+    Ok(json!(words.div_ceil(wpm).max(1)))
+}
+
+fn json_encode_pretty(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    serde_json::to_string_pretty(value)
+        .map(Value::String)
+        .map_err(|e| tera::Error::msg(format!("json_encode_pretty: {}", e)))
+}
+
+fn optional_str<'a>(args: &'a HashMap<String, Value>, name: &str) -> tera::Result<Option<&'a str>> {
+    match args.get(name) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(tera::Error::msg(format!("`{}` must be a string", name))),
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tera::Context;
+
+    fn render(template: &str) -> tera::Result<String> {
+        let mut tera = Tera::default();
+        register(&mut tera);
+        tera.render_str(template, &Context::new())
+    }
+
+    #[test]
+    fn test_date_keeps_builtin_inputs_and_arguments() {
+        assert_eq!(
+            render(r#"{{ "2026-01-03" | date }}"#).unwrap(),
+            "January 3, 2026"
+        );
+        assert_eq!(
+            render(r#"{{ "2026-01-03" | date(format="%d %B %Y", locale="fr_FR") }}"#).unwrap(),
+            "03 janvier 2026"
+        );
+        // numeric timestamps and timezones are handled by Tera's own filter
+        assert_eq!(
+            render(r#"{{ 1767225600 | date(format="%Y-%m-%d %H:%M") }}"#).unwrap(),
+            "2026-01-01 00:00"
+        );
+        assert_eq!(
+            render(
+                r#"{{ "2026-01-01T00:30:00+00:00" | date(format="%H:%M", timezone="Europe/Paris") }}"#
+            )
+            .unwrap(),
+            "01:30"
+        );
+        assert!(render(r#"{{ "soon" | date }}"#).is_err());
+    }
+
+    #[test]
+    fn test_text_filters() {
+        assert_eq!(
+            render(r#"{{ "One two three four" | truncate_words(count=2, end="...") }}"#).unwrap(),
+            "One two..."
+        );
+        assert_eq!(
+            render(r#"{{ "Hello, World!" | slugify }}"#).unwrap(),
+            "hello-world"
+        );
+        assert_eq!(
+            render(r#"{{ "*Hi*" | markdown(inline=true) }}"#).unwrap(),
+            "<em>Hi</em>"
+        );
+        let words = "word ".repeat(401);
+        assert_eq!(
+            render(&format!("{{{{ \"<p>{}</p>\" | reading_time }}}}", words)).unwrap(),
+            "3"
+        );
+    }
+}
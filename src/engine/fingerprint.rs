@@ -0,0 +1,217 @@
+use crate::engine::manifest;
+use crate::models::{ProjectPaths, SiteConfig};
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Written to dist/static next to the assets it lists.
+const MANIFEST_FILE: &str = "asset-manifest.json";
+
+/// Content-hashed names of the files under dist/static, keyed by their plain path
+/// relative to it: `"css/style.css" -> "css/style.3f9a1c2b.css"`. Empty outside of
+/// production builds, where `asset_url` resolves to the plain path.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssetManifest {
+    assets: BTreeMap<String, Asset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Asset {
+    /// Fingerprinted path, relative to dist/static
+    file: String,
+    /// Subresource Integrity value (`sha384-…`) for scripts and stylesheets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    integrity: Option<String>,
+}
+
+impl AssetManifest {
+    /// Write a content-hashed copy of every static file and the stylesheet next to
+    /// the original, then the manifest listing them. Originals are kept so
+    /// hand-written links keep working.
+    pub fn write(paths: &ProjectPaths, config: &SiteConfig) -> Result<Self> {
+        let static_root = paths.dist_static();
+        let prefix = format!("{}/", dist_relative(paths, &static_root));
+
+        let mut assets = BTreeMap::new();
+        for output in manifest::output_files(paths, config, std::iter::empty())? {
+            let Some(relative) = output.strip_prefix(&prefix) else {
+                continue;
+            };
+
+            let source = static_root.join(relative);
+            let contents =
+                fs::read(&source).with_context(|| format!("Failed to read asset {:?}", source))?;
+            let hash = format!("{:x}", Sha256::digest(&contents));
+            let hashed = hashed_name(relative, &hash[..8]);
+
+            manifest::write_if_changed(&static_root.join(&hashed), &contents)?;
+
+            let is_subresource = [".js", ".mjs", ".css"]
+                .iter()
+                .any(|ext| relative.ends_with(ext));
+            let integrity = is_subresource
+                .then(|| format!("sha384-{}", STANDARD.encode(Sha384::digest(&contents))));
+            assets.insert(
+                relative.to_string(),
+                Asset {
+                    file: hashed,
+                    integrity,
+                },
+            );
+        }
+
+        let manifest = Self { assets };
+        let json =
+            serde_json::to_vec_pretty(&manifest).context("Failed to serialize asset manifest")?;
+        manifest::write_if_changed(&static_root.join(MANIFEST_FILE), &json)?;
+        Ok(manifest)
+    }
+
+    /// The manifest a production build left in `static_dir` (dist/static), or an
+    /// empty one if there is none.
+    pub fn load(static_dir: &Path) -> Self {
+        fs::read(static_dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Every fingerprinted path, relative to dist/static.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.assets.values().map(|asset| asset.file.as_str())
+    }
+
+    /// The fingerprinted path for `path` (relative to static/), or `path` itself.
+    pub fn resolve<'a>(&'a self, path: &'a str) -> &'a str {
+        let path = path.trim_start_matches('/');
+        self.assets
+            .get(path)
+            .map_or(path, |asset| asset.file.as_str())
+    }
+
+    /// The SRI value for a fingerprinted script or stylesheet, or `""`, which
+    /// browsers treat as "no integrity check".
+    pub fn integrity(&self, path: &str) -> &str {
+        let path = path.trim_start_matches('/');
+        self.assets
+            .get(path)
+            .and_then(|asset| asset.integrity.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Files [`AssetManifest::write`] produced, relative to dist/.
+    pub fn outputs(&self, paths: &ProjectPaths) -> Vec<String> {
+        if self.assets.is_empty() {
+            return Vec::new();
+        }
+
+        let prefix = dist_relative(paths, &paths.dist_static());
+        self.files()
+            .chain([MANIFEST_FILE])
+            .map(|file| format!("{}/{}", prefix, file))
+            .collect()
+    }
+}
+
+/// `css/style.css` -> `css/style.<hash>.css`
+fn hashed_name(relative: &str, hash: &str) -> String {
+    let (dir, file) = relative
+        .rsplit_once('/')
+        .map_or(("", relative), |(d, f)| (d, f));
+    let file = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}.{}.{}", stem, hash, ext),
+        _ => format!("{}.{}", file, hash),
+    };
+    if dir.is_empty() {
+        file
+    } else {
+        format!("{}/{}", dir, file)
+    }
+}
+
+fn dist_relative(paths: &ProjectPaths, path: &Path) -> String {
+    path.strip_prefix(&paths.dist)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{self, render::Renderer};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_hashed_name_goes_before_the_last_extension() {
+        assert_eq!(hashed_name("css/style.css", "1a2b"), "css/style.1a2b.css");
+        assert_eq!(hashed_name("js/app.min.js", "1a2b"), "js/app.min.1a2b.js");
+        assert_eq!(hashed_name("favicon.ico", "1a2b"), "favicon.1a2b.ico");
+        assert_eq!(hashed_name("img/.hidden", "1a2b"), "img/.hidden.1a2b");
+        assert_eq!(hashed_name("LICENSE", "1a2b"), "LICENSE.1a2b");
+    }
+
+    #[test]
+    fn test_manifest_resolves_assets_and_lists_its_outputs() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        let config = SiteConfig::default();
+        fs::create_dir_all(paths.static_files.join("js")).unwrap();
+        fs::write(paths.static_files.join("js/app.js"), "console.log(1)").unwrap();
+        fs::write(paths.static_files.join("logo.png"), [0u8; 4]).unwrap();
+        engine::copy_static_assets(&paths, &config).unwrap();
+        fs::write(paths.output_css_file(), "body{}").unwrap();
+
+        let assets = AssetManifest::write(&paths, &config).unwrap();
+
+        let script = assets.resolve("/js/app.js");
+        assert!(
+            script.starts_with("js/app.") && script.ends_with(".js"),
+            "{script}"
+        );
+        assert!(paths.dist_static().join(script).is_file());
+        assert_eq!(assets.resolve("missing.js"), "missing.js");
+        assert!(assets.integrity("js/app.js").starts_with("sha384-"));
+        assert_eq!(assets.integrity("logo.png"), "");
+
+        let outputs = assets.outputs(&paths);
+        assert_eq!(outputs.len(), 4);
+        assert!(outputs.contains(&format!("static/{}", script)));
+        assert!(outputs.contains(&"static/asset-manifest.json".to_string()));
+        assert_eq!(
+            AssetManifest::default().outputs(&paths),
+            Vec::<String>::new()
+        );
+
+        let loaded = AssetManifest::load(&paths.dist_static());
+        assert_eq!(loaded.resolve("js/app.js"), script);
+    }
+
+    #[test]
+    fn test_asset_url_uses_the_renderer_manifest() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("page.txt"),
+            r#"{{ asset_url(path="js/app.js") }} {{ asset_url(path="other.js") }}"#,
+        )
+        .unwrap();
+        let assets: AssetManifest =
+            serde_json::from_str(r#"{"js/app.js": {"file": "js/app.1a2b.js"}}"#).unwrap();
+        let config = SiteConfig::default();
+
+        let mut renderer = Renderer::new(&[dir.path().to_path_buf()], assets).unwrap();
+        renderer.register_site_functions(&[], &config);
+
+        let html = renderer
+            .tera
+            .render("page.txt", &tera::Context::new())
+            .unwrap();
+        assert_eq!(html, "/static/js/app.1a2b.js /static/other.js");
+    }
+}
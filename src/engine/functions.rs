@@ -0,0 +1,385 @@
+use crate::engine::fingerprint::AssetManifest;
+use crate::engine::utils;
+use crate::models::{Post, SiteConfig};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tera::Tera;
+
+/// Read-only lookup tables behind the `get_page`, `get_section` and `get_taxonomy` functions.
+pub struct SiteIndex {
+    config: SiteConfig,
+    /// Page objects in listing order (posts newest first, then template pages, then collections)
+    pages: Vec<Value>,
+    by_slug: HashMap<String, usize>,
+    /// tag -> indices into `pages`
+    tags: BTreeMap<String, Vec<usize>>,
+}
+
+impl SiteIndex {
+    /// `pages` are the slugs of the templates under `pages/`.
+    pub fn new(posts: &[Post], pages: &[String], config: &SiteConfig) -> Self {
+        let mut index = Self {
+            config: config.clone(),
+            pages: Vec::new(),
+            by_slug: HashMap::new(),
+            tags: BTreeMap::new(),
+        };
+
+        for post in posts {
+            let i = index.push(
+                &post.slug,
+                json!({
+                    "kind": "post",
+                    "metadata": post.metadata,
+                }),
+            );
+            for tag in &post.metadata.tags {
+                index.tags.entry(tag.clone()).or_default().push(i);
+            }
+        }
+
+        for slug in pages {
+            index.push(slug, json!({ "kind": "page" }));
+        }
+
+        for entry in config.collection_entries.values().flatten() {
+            index.push(
+                &entry.slug,
+                json!({
+                    "kind": "collection",
+                    "record": entry.record,
+                }),
+            );
+        }
+
+        index
+    }
+
+    fn push(&mut self, slug: &str, mut page: Value) -> usize {
+        // where engine::output_path() writes the two root pages
+        let path = match slug {
+            "index" => "",
+            "404" => "404.html",
+            _ => slug,
+        };
+        page["slug"] = json!(slug);
+        page["url"] = json!(self.config.url_for(path, false));
+        page["permalink"] = json!(self.config.url_for(path, true));
+
+        let i = self.pages.len();
+        self.pages.push(page);
+        self.by_slug.insert(slug.to_string(), i);
+        i
+    }
+
+    pub fn page(&self, path: &str) -> Option<&Value> {
+        self.by_slug
+            .get(&normalize_path(path))
+            .map(|&i| &self.pages[i])
+    }
+
+    /// A section is a content directory: its direct pages plus its immediate subsections.
+    pub fn section(&self, path: &str) -> Option<Value> {
+        let path = normalize_path(path);
+        let mut pages = Vec::new();
+        let mut subsections: Vec<String> = Vec::new();
+
+        for page in &self.pages {
+            let slug = page["slug"].as_str().unwrap_or_default();
+            let rest = if path.is_empty() {
+                slug
+            } else {
+                match slug.strip_prefix(&path).and_then(|r| r.strip_prefix('/')) {
+                    Some(rest) => rest,
+                    None => continue,
+                }
+            };
+
+            match rest.split_once('/') {
+                None => pages.push(page.clone()),
+                Some((child, _)) => {
+                    let sub = if path.is_empty() {
+                        child.to_string()
+                    } else {
+                        format!("{}/{}", path, child)
+                    };
+                    if !subsections.contains(&sub) {
+                        subsections.push(sub);
+                    }
+                }
+            }
+        }
+
+        if pages.is_empty() && subsections.is_empty() {
+            return None;
+        }
+
+        let subsections: Vec<Value> = subsections
+            .iter()
+            .map(|sub| {
+                json!({
+                    "path": sub,
+                    "url": self.config.url_for(sub, false),
+                })
+            })
+            .collect();
+
+        Some(json!({
+            "path": path,
+            "url": self.config.url_for(&path, false),
+            "pages": pages,
+            "subsections": subsections,
+        }))
+    }
+
+    /// No page is generated per term, so there is no `url`; `slug` is there for
+    /// building anchors or links to pages the site provides itself.
+    fn term(&self, name: &str, indices: &[usize]) -> Value {
+        json!({
+            "name": name,
+            "slug": utils::Slugify::from_text(name),
+            "pages": indices.iter().map(|&i| &self.pages[i]).collect::<Vec<_>>(),
+        })
+    }
+
+    /// A single taxonomy term, or every term when `term` is `None`.
+    pub fn taxonomy(&self, kind: &str, term: Option<&str>) -> tera::Result<Value> {
+        if kind != "tags" {
+            return Err(format!("Unknown taxonomy '{}' (only 'tags' is supported)", kind).into());
+        }
+
+        match term {
+            Some(term) => Ok(self.term(term, self.tags.get(term).map_or(&[], |v| v))),
+            None => Ok(Value::Array(
+                self.tags
+                    .iter()
+                    .map(|(name, indices)| self.term(name, indices))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Register the site lookup functions and `url_for` on `tera`.
+pub fn register(tera: &mut Tera, index: SiteIndex) {
+    let index = Arc::new(index);
+
+    let site = Arc::clone(&index);
+    tera.register_function("get_page", move |args: &HashMap<String, Value>| {
+        let path = required_str(args, "get_page", "path")?;
+        site.page(path)
+            .cloned()
+            .ok_or_else(|| format!("get_page: no page found at '{}'", path).into())
+    });
+
+    let site = Arc::clone(&index);
+    tera.register_function("get_section", move |args: &HashMap<String, Value>| {
+        let path = required_str(args, "get_section", "path")?;
+        site.section(path)
+            .ok_or_else(|| format!("get_section: no section found at '{}'", path).into())
+    });
+
+    let site = Arc::clone(&index);
+    tera.register_function("get_taxonomy", move |args: &HashMap<String, Value>| {
+        let kind = required_str(args, "get_taxonomy", "kind")?;
+        let term = args.get("term").and_then(Value::as_str);
+        site.taxonomy(kind, term)
+    });
+
+    let site = Arc::clone(&index);
+    tera.register_function("url_for", move |args: &HashMap<String, Value>| {
+        let path = required_str(args, "url_for", "path")?;
+        let absolute = args
+            .get("absolute")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        Ok(json!(site.config.url_for(path, absolute)))
+    });
+}
+
+/// Register `asset_url`, which resolves a file under static/ to its public URL
+/// (fingerprinted when `assets` lists it), and `asset_integrity`, its SRI hash.
+pub fn register_asset_url(tera: &mut Tera, config: &SiteConfig, assets: AssetManifest) {
+    let assets = Arc::new(assets);
+    let site = SiteConfig {
+        base_url: config.base_url.clone(),
+        ..SiteConfig::default()
+    };
+
+    let manifest = Arc::clone(&assets);
+    tera.register_function("asset_integrity", move |args: &HashMap<String, Value>| {
+        let path = required_str(args, "asset_integrity", "path")?;
+        Ok(json!(manifest.integrity(path)))
+    });
+
+    tera.register_function("asset_url", move |args: &HashMap<String, Value>| {
+        let path = required_str(args, "asset_url", "path")?;
+        let absolute = args
+            .get("absolute")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let path = format!("static/{}", assets.resolve(path));
+        Ok(json!(site.url_for(&path, absolute)))
+    });
+}
+
+fn required_str<'a>(
+    args: &'a HashMap<String, Value>,
+    function: &str,
+    name: &str,
+) -> tera::Result<&'a str> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("{}: missing string argument '{}'", function, name).into())
+}
+
+/// Accept "posts/hello", "/posts/hello/" and "posts/hello.md" alike.
+fn normalize_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+    path.strip_suffix(".md").unwrap_or(path).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CollectionEntry, PostMetadata};
+    use chrono::NaiveDate;
+    use std::path::PathBuf;
+
+    fn post(slug: &str, day: u32, tags: &[&str]) -> Post {
+        Post {
+            metadata: PostMetadata {
+                title: slug.to_string(),
+                date: NaiveDate::from_ymd_opt(2025, 1, day).unwrap(),
+                description: None,
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                draft: false,
+            },
+            content: String::new(),
+            html: String::new(),
+            slug: slug.to_string(),
+            source: PathBuf::from(format!("content/{}.md", slug)),
+        }
+    }
+
+    /// Posts newest first, as the renderer passes them.
+    fn index() -> SiteIndex {
+        let posts = [
+            post("posts/2025/c", 3, &["rust", "web"]),
+            post("posts/2025/b", 2, &["rust"]),
+            post("posts/a", 1, &[]),
+        ];
+        let mut config = SiteConfig {
+            base_url: "https://example.com/blog/".to_string(),
+            ..SiteConfig::default()
+        };
+        config.collection_entries.insert(
+            "team".to_string(),
+            vec![CollectionEntry {
+                slug: "team/ann".to_string(),
+                template: "member.html".to_string(),
+                record: json!({ "name": "Ann" }),
+            }],
+        );
+        let pages = ["about".to_string(), "index".to_string()];
+        SiteIndex::new(&posts, &pages, &config)
+    }
+
+    #[test]
+    fn test_get_page_finds_posts_template_pages_and_collection_entries() {
+        let index = index();
+
+        let post = index.page("/posts/2025/B.md/").unwrap();
+        assert_eq!(post["kind"], "post");
+        assert_eq!(post["metadata"]["title"], "posts/2025/b");
+        assert_eq!(post["url"], "/blog/posts/2025/b");
+        assert_eq!(post["permalink"], "https://example.com/blog/posts/2025/b");
+
+        let about = index.page("about").unwrap();
+        assert_eq!(about["kind"], "page");
+        assert_eq!(about["url"], "/blog/about");
+        assert_eq!(index.page("index").unwrap()["url"], "/blog/");
+
+        let member = index.page("team/ann").unwrap();
+        assert_eq!(member["record"]["name"], "Ann");
+
+        assert!(index.page("posts/missing").is_none());
+    }
+
+    #[test]
+    fn test_get_section_lists_direct_pages_and_subsections() {
+        let index = index();
+
+        let posts = index.section("posts").unwrap();
+        let slugs: Vec<&Value> = posts["pages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| &p["slug"])
+            .collect();
+        assert_eq!(slugs, [&json!("posts/a")]);
+        assert_eq!(posts["subsections"][0]["path"], "posts/2025");
+        assert_eq!(posts["subsections"][0]["url"], "/blog/posts/2025");
+
+        let year = index.section("posts/2025/").unwrap();
+        assert_eq!(year["pages"][0]["slug"], "posts/2025/c");
+        assert_eq!(year["pages"][1]["slug"], "posts/2025/b");
+        assert_eq!(year["subsections"], json!([]));
+
+        assert!(index.section("drafts").is_none());
+    }
+
+    #[test]
+    fn test_get_taxonomy_lists_tagged_pages() {
+        let index = index();
+
+        let rust = index.taxonomy("tags", Some("rust")).unwrap();
+        assert_eq!(rust["slug"], "rust");
+        assert_eq!(rust["pages"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            index.taxonomy("tags", Some("go")).unwrap()["pages"],
+            json!([])
+        );
+
+        let terms = index.taxonomy("tags", None).unwrap();
+        let names: Vec<&Value> = terms
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| &t["name"])
+            .collect();
+        assert_eq!(names, [&json!("rust"), &json!("web")]);
+
+        assert!(index.taxonomy("categories", None).is_err());
+    }
+
+    #[test]
+    fn test_lookup_misses_fail_the_render_with_the_path() {
+        let mut tera = Tera::default();
+        register(&mut tera, index());
+
+        let page = tera
+            .render_str(
+                r#"{% set about = get_page(path="about") %}{{ about.url }}"#,
+                &tera::Context::new(),
+            )
+            .unwrap();
+        assert_eq!(page, "/blog/about");
+
+        for (template, expected) in [
+            (r#"{{ get_page(path="nope") }}"#, "no page found at 'nope'"),
+            (
+                r#"{{ get_section(path="nope") }}"#,
+                "no section found at 'nope'",
+            ),
+            (r#"{{ get_page() }}"#, "missing string argument 'path'"),
+        ] {
+            let err = tera
+                .render_str(template, &tera::Context::new())
+                .unwrap_err();
+            let message = format!("{:?}", err);
+            assert!(message.contains(expected), "{message}");
+        }
+    }
+}
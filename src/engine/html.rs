@@ -0,0 +1,264 @@
+use std::ops::Range;
+
+#[derive(Debug)]
+pub enum Token<'a> {
+    Start(Tag),
+    End {
+        name: String,
+        span: Range<usize>,
+    },
+    Text(&'a str),
+    /// Contents of `<script>` or `<style>`, which must never be treated as markup
+    RawText(&'a str),
+    /// Comments, doctype and processing instructions, verbatim
+    Other(&'a str),
+}
+
+#[derive(Debug)]
+pub struct Tag {
+    /// Lowercased element name
+    pub name: String,
+    /// Lowercased attribute names with entity-decoded values (`""` for bare attributes)
+    pub attrs: Vec<(String, String)>,
+    pub self_closing: bool,
+    /// Byte range of the whole tag in the source, `<` through `>`
+    pub span: Range<usize>,
+}
+
+impl Tag {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attrs.iter().any(|(key, _)| key == name)
+    }
+}
+
+/// Elements whose content is raw text rather than markup.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Split rendered HTML into tags, text, raw text and comments. This is a forgiving
+/// scanner for post-render passes, not a parser: it never builds a tree.
+pub fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let bytes = html.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut text_start = 0;
+
+    while let Some(found) = html[pos..].find('<') {
+        let lt = pos + found;
+        let next = bytes.get(lt + 1).copied();
+
+        let (token, end) = if html[lt..].starts_with("<!--") {
+            let end = html[lt + 4..]
+                .find("-->")
+                .map_or(html.len(), |i| lt + 4 + i + 3);
+            (Token::Other(&html[lt..end]), end)
+        } else if matches!(next, Some(b'!') | Some(b'?')) {
+            let end = html[lt..].find('>').map_or(html.len(), |i| lt + i + 1);
+            (Token::Other(&html[lt..end]), end)
+        } else if next == Some(b'/') && bytes.get(lt + 2).is_some_and(u8::is_ascii_alphabetic) {
+            let end = html[lt..].find('>').map_or(html.len(), |i| lt + i + 1);
+            let name = html[lt + 2..end]
+                .trim_end_matches('>')
+                .trim()
+                .to_ascii_lowercase();
+            (
+                Token::End {
+                    name,
+                    span: lt..end,
+                },
+                end,
+            )
+        } else if next.is_some_and(|b| b.is_ascii_alphabetic()) {
+            let tag = parse_tag(html, lt);
+            let end = tag.span.end;
+            (Token::Start(tag), end)
+        } else {
+            // a stray '<' is just text
+            pos = lt + 1;
+            continue;
+        };
+
+        if text_start < lt {
+            tokens.push(Token::Text(&html[text_start..lt]));
+        }
+
+        let raw_text_end = match &token {
+            Token::Start(tag)
+                if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) && !tag.self_closing =>
+            {
+                Some(find_closing_tag(html, end, &tag.name))
+            }
+            _ => None,
+        };
+        tokens.push(token);
+        pos = end;
+
+        if let Some(close) = raw_text_end {
+            if end < close {
+                tokens.push(Token::RawText(&html[end..close]));
+            }
+            pos = close;
+        }
+        text_start = pos;
+    }
+
+    if text_start < html.len() {
+        tokens.push(Token::Text(&html[text_start..]));
+    }
+    tokens
+}
+
+pub fn start_tags<'a>(tokens: &'a [Token]) -> impl Iterator<Item = &'a Tag> {
+    tokens.iter().filter_map(|token| match token {
+        Token::Start(tag) => Some(tag),
+        _ => None,
+    })
+}
+
+/// Offset of `</name` at or after `from`, or the end of input.
+fn find_closing_tag(html: &str, from: usize, name: &str) -> usize {
+    let needle = format!("</{}", name);
+    html[from..]
+        .to_ascii_lowercase()
+        .find(&needle)
+        .map_or(html.len(), |i| from + i)
+}
+
+fn parse_tag(html: &str, lt: usize) -> Tag {
+    let bytes = html.as_bytes();
+    let mut i = lt + 1;
+
+    let name_start = i;
+    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' && bytes[i] != b'/'
+    {
+        i += 1;
+    }
+    let name = html[name_start..i].to_ascii_lowercase();
+
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => break,
+            Some(b'>') => {
+                i += 1;
+                break;
+            }
+            Some(b'/') => {
+                i += 1;
+                if bytes.get(i) == Some(&b'>') {
+                    self_closing = true;
+                    i += 1;
+                    break;
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let key_start = i;
+        while i < bytes.len()
+            && !bytes[i].is_ascii_whitespace()
+            && !matches!(bytes[i], b'=' | b'>' | b'/')
+        {
+            i += 1;
+        }
+        let key = html[key_start..i].to_ascii_lowercase();
+
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let value_start = i + 1;
+                    let value_end = html[value_start..]
+                        .find(quote as char)
+                        .map_or(html.len(), |j| value_start + j);
+                    value = decode_entities(&html[value_start..value_end]);
+                    i = (value_end + 1).min(html.len());
+                }
+                _ => {
+                    let value_start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    value = decode_entities(&html[value_start..i]);
+                }
+            }
+        }
+
+        if !key.is_empty() {
+            attrs.push((key, value));
+        }
+    }
+
+    Tag {
+        name,
+        attrs,
+        self_closing,
+        span: lt..i,
+    }
+}
+
+/// Decode numeric references and the named entities Tera and pulldown-cmark emit.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(|code| code.ok())
+                    .and_then(char::from_u32),
+            };
+            ch.map(|ch| (ch, end + 1))
+        });
+
+        match decoded {
+            Some((ch, len)) => {
+                out.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
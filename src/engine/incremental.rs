@@ -0,0 +1,527 @@
+use crate::engine::cache::PostCache;
+use crate::engine::fingerprint::AssetManifest;
+use crate::engine::render::{PageRef, Renderer};
+use crate::engine::{self, collections, data, parser, theme, write_pages};
+use crate::models::{BuildOptions, Post, ProjectPaths, SiteConfig};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Which templates each template pulls in, and which site-wide inputs they read.
+/// Built by scanning template sources, so it errs on the side of "depends".
+#[derive(Default)]
+pub struct DependencyGraph {
+    /// Template name -> itself plus everything it extends, includes or imports (transitively)
+    closures: BTreeMap<String, BTreeSet<String>>,
+    /// Templates that call `get_page`, `get_section` or `get_taxonomy`
+    uses_site_index: BTreeSet<String>,
+    /// Templates that read `data` or `collections`, both built from data/
+    uses_data: BTreeSet<String>,
+}
+
+impl DependencyGraph {
+    pub fn new(templates: &BTreeMap<String, PathBuf>) -> Result<Self> {
+        let mut graph = Self::default();
+        let mut direct: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+
+        for (name, path) in templates {
+            let source = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read template {:?}", path))?;
+
+            let mut deps = BTreeSet::new();
+            for tag in tag_bodies(&source) {
+                let tag = tag.trim_start_matches(['-', ' ', '\t', '\n', '\r']);
+                let keyword = tag.split_whitespace().next().unwrap_or_default();
+                if matches!(keyword, "extends" | "include" | "import") {
+                    for quoted in tag.split(['"', '\'']).skip(1).step_by(2) {
+                        deps.insert(quoted.to_string());
+                    }
+                }
+
+                if ["get_page", "get_section", "get_taxonomy"]
+                    .iter()
+                    .any(|f| mentions(tag, f))
+                {
+                    graph.uses_site_index.insert(name.clone());
+                }
+                if mentions(tag, "data") || mentions(tag, "collections") {
+                    graph.uses_data.insert(name.clone());
+                }
+            }
+            direct.insert(name, deps);
+        }
+
+        for name in templates.keys() {
+            let mut closure = BTreeSet::new();
+            let mut stack = vec![name.clone()];
+            while let Some(current) = stack.pop() {
+                if closure.insert(current.clone())
+                    && let Some(deps) = direct.get(current.as_str())
+                {
+                    stack.extend(deps.iter().cloned());
+                }
+            }
+            graph.closures.insert(name.clone(), closure);
+        }
+
+        Ok(graph)
+    }
+
+    fn closure(&self, template: &str) -> impl Iterator<Item = &String> {
+        self.closures.get(template).into_iter().flatten()
+    }
+
+    /// `template` is one of `names` or pulls one of them in.
+    pub fn includes_any(&self, template: &str, names: &BTreeSet<String>) -> bool {
+        self.closure(template).any(|t| names.contains(t))
+    }
+
+    fn reads_site_index(&self, template: &str) -> bool {
+        self.closure(template)
+            .any(|t| self.uses_site_index.contains(t))
+    }
+
+    fn reads_data(&self, template: &str) -> bool {
+        self.closure(template).any(|t| self.uses_data.contains(t))
+    }
+}
+
+/// The contents of every `{{ }}` and `{% %}` tag in a template.
+fn tag_bodies(source: &str) -> impl Iterator<Item = &str> {
+    let mut rest = source;
+    std::iter::from_fn(move || {
+        loop {
+            let open = rest.find('{')?;
+            let close = match rest[open + 1..].chars().next()? {
+                '{' => "}}",
+                '%' => "%}",
+                _ => {
+                    rest = &rest[open + 1..];
+                    continue;
+                }
+            };
+            let body_start = open + 2;
+            let body_end = rest[body_start..]
+                .find(close)
+                .map_or(rest.len(), |i| body_start + i);
+            let body = &rest[body_start..body_end];
+            rest = &rest[(body_end + 2).min(rest.len())..];
+            return Some(body);
+        }
+    })
+}
+
+/// `ident` appears in `text` as a whole identifier (not `metadata` for `data`).
+fn mentions(text: &str, ident: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(ident).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + ident.len()..].chars().next();
+        !before.is_some_and(|c| is_ident(c) || c == '.') && !after.is_some_and(is_ident)
+    })
+}
+
+/// How a changed file affects the site.
+enum Change {
+    /// site.toml, themes, or templates being added or removed
+    Full,
+    Static,
+    Post(PathBuf),
+    Template(String),
+    Data,
+    Ignore,
+}
+
+/// What a [`Site::rebuild`] ended up doing.
+pub enum Rebuilt {
+    Everything,
+    Pages(usize),
+}
+
+/// A rendered site kept in memory by the dev server so a change only re-renders
+/// the pages that depend on it. The compiled templates live as long as the site;
+/// only the ones that changed are reloaded.
+pub struct Site {
+    paths: ProjectPaths,
+    options: BuildOptions,
+    config: SiteConfig,
+    posts: Vec<Post>,
+    renderer: Renderer,
+    graph: DependencyGraph,
+    /// Every page the site currently produces, to prune outputs of removed ones
+    slugs: Vec<String>,
+}
+
+impl Site {
+    /// Copy static files, then parse, render and write everything.
+    pub fn build(paths: &ProjectPaths, options: &BuildOptions) -> Result<Self> {
+        let config = engine::load_config(paths)?;
+        engine::copy_static_assets(paths, &config).context("Copying static assets failed")?;
+
+        let cache = PostCache::load(&paths.cache);
+        let mut posts = parser::parse_all_posts_cached(&paths.content, Some(&cache))?;
+        cache.save()?;
+        posts.retain(|post| options.includes(post));
+
+        let mut renderer = Renderer::new(
+            &theme::template_dirs(paths, &config),
+            AssetManifest::default(),
+        )?;
+        renderer.register_site_functions(&posts, &config);
+        let graph = DependencyGraph::new(&renderer.templates)?;
+
+        let pages = renderer
+            .render_all(&posts, &config)
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .context("Rendering site failed")?;
+        write_pages(&paths.dist, &pages)?;
+
+        let slugs: Vec<String> = pages.into_iter().map(|page| page.slug).collect();
+        engine::prune_dist(paths, &config, slugs.iter().map(String::as_str), [])?;
+
+        Ok(Self {
+            paths: paths.clone(),
+            options: options.clone(),
+            renderer,
+            config,
+            posts,
+            graph,
+            slugs,
+        })
+    }
+
+    /// Bring dist/ up to date with `changed` files, re-rendering only the pages
+    /// that depend on them. On error the site should be rebuilt from scratch.
+    pub fn rebuild(&mut self, changed: &[PathBuf]) -> Result<Rebuilt> {
+        let changes: Vec<Change> = changed.iter().map(|path| self.classify(path)).collect();
+        if changes.iter().any(|c| matches!(c, Change::Full)) {
+            *self = Self::build(&self.paths, &self.options)?;
+            return Ok(Rebuilt::Everything);
+        }
+
+        let mut changed_posts = BTreeSet::new();
+        let mut dirty_templates = BTreeSet::new();
+        let mut data_changed = false;
+        let mut static_changed = false;
+        for change in changes {
+            match change {
+                Change::Static => static_changed = true,
+                Change::Post(path) => {
+                    changed_posts.insert(path);
+                }
+                Change::Template(name) => {
+                    dirty_templates.insert(name);
+                }
+                Change::Data => data_changed = true,
+                Change::Full | Change::Ignore => {}
+            }
+        }
+
+        if static_changed {
+            engine::copy_static_assets(&self.paths, &self.config)
+                .context("Copying static assets failed")?;
+        }
+        if data_changed {
+            self.config.data =
+                data::load_data_dir(&self.paths.data).context("Failed to load data files")?;
+            self.config.collection_entries = collections::resolve_collections(&self.config)
+                .context("Failed to resolve collections")?;
+        }
+        for path in &changed_posts {
+            self.reparse_post(path)?;
+        }
+        let posts_changed = !changed_posts.is_empty();
+
+        if !posts_changed && dirty_templates.is_empty() && !data_changed {
+            if static_changed {
+                self.prune()?;
+            }
+            return Ok(Rebuilt::Pages(0));
+        }
+
+        // Markdown-only edits leave the compiled templates alone
+        if !dirty_templates.is_empty() {
+            self.renderer.reload_templates(&dirty_templates)?;
+            self.graph = DependencyGraph::new(&self.renderer.templates)?;
+        }
+        if posts_changed || data_changed {
+            self.renderer
+                .register_site_functions(&self.posts, &self.config);
+        }
+
+        let graph = &self.graph;
+        let pages = self
+            .renderer
+            .render_where(&self.posts, &self.config, |page| {
+                let template = page.template();
+                let own_source_changed = match page {
+                    PageRef::Post(post) => changed_posts.contains(&post.source),
+                    PageRef::Collection(_) => data_changed,
+                    PageRef::Page(_) => false,
+                };
+                // page templates always receive the full `posts` list
+                let reads_posts =
+                    matches!(page, PageRef::Page(_)) || graph.reads_site_index(template);
+                // the site index lists collection entries as well as posts
+                let reads_data = graph.reads_data(template) || graph.reads_site_index(template);
+
+                own_source_changed
+                    || graph.includes_any(template, &dirty_templates)
+                    || (posts_changed && reads_posts)
+                    || (data_changed && reads_data)
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .context("Rendering site failed")?;
+
+        write_pages(&self.paths.dist, &pages)?;
+
+        self.slugs = self.renderer.slugs(&self.posts, &self.config)?;
+        self.prune()?;
+        Ok(Rebuilt::Pages(pages.len()))
+    }
+
+    fn prune(&self) -> Result<()> {
+        engine::prune_dist(
+            &self.paths,
+            &self.config,
+            self.slugs.iter().map(String::as_str),
+            [],
+        )
+    }
+
+    fn classify(&self, path: &Path) -> Change {
+        let relative = |root: &Path| relative_to(path, root);
+
+        if relative(&self.paths.config).is_some() || relative(&self.paths.themes).is_some() {
+            Change::Full
+        } else if relative(&self.paths.static_files).is_some() {
+            Change::Static
+        } else if relative(&self.paths.data).is_some() {
+            Change::Data
+        } else if let Some(name) = relative(&self.paths.templates) {
+            // the name may be served by a theme's template the project is only now overriding
+            let loaded = self
+                .renderer
+                .templates
+                .get(&name)
+                .is_some_and(|loaded| same_path(loaded, path));
+            match (loaded, path.exists()) {
+                (true, true) => Change::Template(name),
+                // an editor's temp file, already renamed over the real one
+                (false, false) => Change::Ignore,
+                // new or deleted templates change what Tera can resolve
+                _ => Change::Full,
+            }
+        } else if let Some(rest) = relative(&self.paths.content)
+            && rest.starts_with("posts/")
+            && rest.ends_with(".md")
+        {
+            Change::Post(self.paths.content.join(rest))
+        } else {
+            Change::Ignore
+        }
+    }
+
+    /// Re-read one post (or drop it if it was deleted or became a hidden draft).
+    fn reparse_post(&mut self, path: &Path) -> Result<()> {
+        self.posts.retain(|post| post.source != path);
+
+        if path.exists() {
+            let post = parser::parse_post(path, &self.paths.content)?;
+            if self.options.includes(&post) {
+                self.posts.push(post);
+            }
+        }
+
+        self.posts
+            .sort_by_key(|p| std::cmp::Reverse(p.metadata.date));
+        Ok(())
+    }
+}
+
+/// `path` relative to `root` as a `/`-separated string, when it's inside it.
+/// Handles the absolute paths file watchers report against relative project paths.
+fn relative_to(path: &Path, root: &Path) -> Option<String> {
+    let root = std::path::absolute(root).ok()?;
+    let path = std::path::absolute(path).ok()?;
+    let relative = path.strip_prefix(&root).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    matches!((std::path::absolute(a), std::path::absolute(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, relative: &str, contents: &str) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn rebuilt_pages(site: &mut Site, changed: &Path) -> usize {
+        match site.rebuild(&[changed.to_path_buf()]).unwrap() {
+            Rebuilt::Pages(count) => count,
+            Rebuilt::Everything => panic!("{:?} triggered a full rebuild", changed),
+        }
+    }
+
+    #[test]
+    fn test_closure_follows_extends_includes_and_imports_transitively() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(root, "base.html", r#"{% include "nav.html" %}"#);
+        write(root, "nav.html", r#"{%- include 'links.html' -%}"#);
+        write(root, "links.html", "{{ post.metadata.title }}");
+        write(root, "macros.html", "{{ get_section(path='posts') }}");
+        write(root, "post.html", r#"{% extends "base.html" %}"#);
+        write(
+            root,
+            "team.html",
+            r#"{% import "macros.html" as m %}{{ data.team }}"#,
+        );
+        let templates = theme::layered_files(&[root.to_path_buf()]).unwrap();
+
+        let graph = DependencyGraph::new(&templates).unwrap();
+
+        let closure: Vec<&String> = graph.closure("post.html").collect();
+        assert_eq!(
+            closure,
+            ["base.html", "links.html", "nav.html", "post.html"]
+        );
+        let links = BTreeSet::from(["links.html".to_string()]);
+        assert!(graph.includes_any("post.html", &links));
+        assert!(!graph.includes_any("team.html", &links));
+        assert!(graph.reads_site_index("team.html"));
+        assert!(!graph.reads_site_index("post.html"));
+        assert!(graph.reads_data("team.html"));
+        // `metadata` is not `data`
+        assert!(!graph.reads_data("post.html"));
+    }
+
+    #[test]
+    fn test_rebuild_renders_only_the_pages_a_change_reaches() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "site.toml",
+            "title = \"T\"\nauthor = \"A\"\nfooter_text = \"\"\n\n\
+             [[collections]]\ndata = \"team.json\"\nslug_field = \"name\"\ntemplate = \"member.html\"\n",
+        );
+        write(
+            root,
+            "templates/base.html",
+            r#"{% include "nav.html" %}{% block body %}{% endblock body %}"#,
+        );
+        write(root, "templates/nav.html", "<nav></nav>");
+        write(
+            root,
+            "templates/post.html",
+            r#"{% extends "base.html" %}{% block body %}{{ content | safe }}{% endblock body %}"#,
+        );
+        write(root, "templates/member.html", "{{ record.name }}");
+        write(root, "templates/pages/index.html", "{{ posts | length }}");
+        write(
+            root,
+            "templates/pages/count.html",
+            "{{ data.team | length }}",
+        );
+        write(
+            root,
+            "templates/pages/people.html",
+            "{% for m in collections.team %}{{ m.record.name }},{% endfor %}",
+        );
+        write(
+            root,
+            "templates/pages/lookup.html",
+            r#"{% set team = get_section(path="team") %}{% for p in team.pages %}{{ p.record.name }},{% endfor %}"#,
+        );
+        let post = write(
+            root,
+            "content/posts/a.md",
+            "---\ntitle: A\ndate: 2025-01-01\n---\nHello\n",
+        );
+        write(
+            root,
+            "content/posts/b.md",
+            "---\ntitle: B\ndate: 2025-01-02\n---\nHi\n",
+        );
+        let data = write(root, "data/team.json", r#"[{"name": "a"}]"#);
+        let paths = ProjectPaths::from_root(root);
+
+        let mut site = Site::build(&paths, &BuildOptions::default()).unwrap();
+        assert!(paths.dist.join("posts/a/index.html").is_file());
+
+        // the post itself and all four pages, which receive every post
+        fs::write(&post, "---\ntitle: A\ndate: 2025-01-01\n---\nChanged\n").unwrap();
+        assert_eq!(rebuilt_pages(&mut site, &post), 5);
+        // both posts, through post.html -> base.html -> nav.html
+        let nav = write(root, "templates/nav.html", "<nav>new</nav>");
+        assert_eq!(rebuilt_pages(&mut site, &nav), 2);
+        // both entries and the pages reading `data`, `collections` or the site index
+        fs::write(&data, r#"[{"name": "a"}, {"name": "b"}]"#).unwrap();
+        assert_eq!(rebuilt_pages(&mut site, &data), 5);
+        let read = |page: &str| fs::read_to_string(paths.dist.join(page)).unwrap();
+        assert_eq!(read("count/index.html"), "2");
+        assert_eq!(read("people/index.html"), "a,b,");
+        assert_eq!(read("lookup/index.html"), "a,b,");
+        assert_eq!(read("team/b/index.html"), "b");
+        let script = write(root, "static/app.js", "");
+        assert_eq!(rebuilt_pages(&mut site, &script), 0);
+    }
+
+    #[test]
+    fn test_template_changes_reload_only_what_the_site_renders_with() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "site.toml",
+            "title = \"T\"\nauthor = \"A\"\nfooter_text = \"\"\ntheme = \"base\"\n",
+        );
+        write(
+            root,
+            "themes/base/templates/post.html",
+            r#"{% include "nav.html" %}theme:{{ post.title }}"#,
+        );
+        write(root, "templates/nav.html", "<nav>1</nav>");
+        let post = write(
+            root,
+            "content/posts/a.md",
+            "---\ntitle: A\ndate: 2025-01-01\n---\n",
+        );
+        let paths = ProjectPaths::from_root(root);
+        let output = paths.dist.join("posts/a/index.html");
+
+        let mut site = Site::build(&paths, &BuildOptions::default()).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "<nav>1</nav>theme:A");
+
+        // a partial the theme's template includes
+        let nav = write(root, "templates/nav.html", "<nav>2</nav>");
+        assert_eq!(rebuilt_pages(&mut site, &nav), 1);
+        assert_eq!(fs::read_to_string(&output).unwrap(), "<nav>2</nav>theme:A");
+
+        // a project template overriding the theme's is not a reload of the theme's
+        let post_template = write(root, "templates/post.html", "project:{{ post.title }}");
+        assert!(matches!(
+            site.rebuild(std::slice::from_ref(&post_template)).unwrap(),
+            Rebuilt::Everything
+        ));
+        assert_eq!(fs::read_to_string(&output).unwrap(), "project:A");
+
+        // a Markdown-only edit keeps the compiled templates
+        fs::write(&post_template, "stale:{{ post.title }}").unwrap();
+        fs::write(&post, "---\ntitle: B\ndate: 2025-01-01\n---\n").unwrap();
+        assert_eq!(rebuilt_pages(&mut site, &post), 1);
+        assert_eq!(fs::read_to_string(&output).unwrap(), "project:B");
+    }
+}
@@ -0,0 +1,250 @@
+use crate::engine::html::{self, Token};
+use crate::engine::output_file;
+use crate::models::{RenderedPage, SiteConfig};
+use anyhow::Result;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+/// An internal `href`/`src` that doesn't resolve to an output file or anchor.
+#[derive(Debug)]
+pub struct BrokenLink {
+    /// Output file the reference appears in, relative to dist/
+    pub page: String,
+    pub url: String,
+    pub reason: String,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.page, self.url, self.reason)
+    }
+}
+
+/// Check every internal reference in `pages` against `files` (paths relative to
+/// dist/, `/`-separated) and `#fragment`s against the ids on the target page.
+/// External URLs are skipped.
+pub fn check_links(
+    pages: &[RenderedPage],
+    files: &BTreeSet<String>,
+    config: &SiteConfig,
+) -> Vec<BrokenLink> {
+    let site_root = config.url_for("", false);
+    let site_origin = config.url_for("", true);
+
+    let parsed: Vec<(String, Vec<Token>)> = pages
+        .iter()
+        .map(|page| (output_file(&page.slug), html::tokenize(&page.html)))
+        .collect();
+
+    let ids: HashMap<&str, HashSet<&str>> = parsed
+        .iter()
+        .map(|(file, tokens)| (file.as_str(), anchor_ids(tokens)))
+        .collect();
+
+    let mut broken = Vec::new();
+    for (file, tokens) in &parsed {
+        for tag in html::start_tags(tokens) {
+            for url in ["href", "src", "poster"].iter().filter_map(|a| tag.attr(a)) {
+                let Some(link) = Link::parse(url, &site_root, &site_origin) else {
+                    continue;
+                };
+
+                if let Err(reason) = link.check(file, files, &ids) {
+                    broken.push(BrokenLink {
+                        page: file.clone(),
+                        url: url.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+    }
+
+    broken
+}
+
+fn anchor_ids<'a>(tokens: &'a [Token]) -> HashSet<&'a str> {
+    html::start_tags(tokens)
+        .filter_map(|tag| {
+            tag.attr("id")
+                .or_else(|| tag.attr("name").filter(|_| tag.name == "a"))
+        })
+        .collect()
+}
+
+struct Link {
+    /// Site path without the base_url prefix: `None` for a same-page `#fragment`,
+    /// `Some("/...")` when absolute, otherwise relative to the linking page
+    path: Option<String>,
+    fragment: Option<String>,
+}
+
+impl Link {
+    /// `None` for external, special-scheme or empty URLs, which aren't checked.
+    fn parse(url: &str, site_root: &str, site_origin: &str) -> Option<Self> {
+        let url = url.trim();
+        if url.is_empty() || url.starts_with("//") {
+            return None;
+        }
+
+        let url = match url.strip_prefix(site_origin) {
+            Some(rest) if site_origin.contains("://") => format!("/{}", rest),
+            _ => {
+                let scheme_end = url.find([':', '/', '?', '#']);
+                if scheme_end.is_some_and(|i| url.as_bytes()[i] == b':') {
+                    return None;
+                }
+                url.to_string()
+            }
+        };
+
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url, Some(percent_decode(fragment))),
+            None => (url.as_str(), None),
+        };
+        let url = url.split('?').next().unwrap_or_default();
+
+        let path = if url.is_empty() {
+            None
+        } else if let Some(rest) = url.strip_prefix(site_root) {
+            Some(format!("/{}", percent_decode(rest)))
+        } else {
+            Some(percent_decode(url))
+        };
+
+        Some(Self {
+            path,
+            fragment: fragment.filter(|f| !f.is_empty()),
+        })
+    }
+
+    fn check(
+        &self,
+        page: &str,
+        files: &BTreeSet<String>,
+        ids: &HashMap<&str, HashSet<&str>>,
+    ) -> Result<(), String> {
+        let target = match &self.path {
+            None => page.to_string(),
+            Some(path) => resolve(page, path, files)?,
+        };
+
+        if let Some(fragment) = &self.fragment
+            && let Some(page_ids) = ids.get(target.as_str())
+            && !page_ids.contains(fragment.as_str())
+        {
+            return Err(format!("no element with id \"{}\" on {}", fragment, target));
+        }
+        Ok(())
+    }
+}
+
+/// Resolve `path` as linked from the dist file `page` to an existing dist file.
+fn resolve(page: &str, path: &str, files: &BTreeSet<String>) -> Result<String, String> {
+    let mut segments: Vec<&str> = if path.starts_with('/') {
+        Vec::new()
+    } else {
+        let mut dir: Vec<&str> = page.split('/').collect();
+        dir.pop();
+        dir
+    };
+
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if segments.pop().is_none() {
+                    return Err("points outside the site root".to_string());
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let joined = segments.join("/");
+    let candidates = if path.ends_with('/') || joined.is_empty() {
+        vec![format!("{}/index.html", joined)]
+    } else {
+        vec![joined.clone(), format!("{}/index.html", joined)]
+    };
+
+    candidates
+        .into_iter()
+        .map(|c| c.trim_start_matches('/').to_string())
+        .find(|c| files.contains(c))
+        .ok_or_else(|| "no such file in the build".to_string())
+}
+
+fn percent_decode(text: &str) -> String {
+    if !text.contains('%') {
+        return text.to_string();
+    }
+
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = text.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn page(slug: &str, html: &str) -> RenderedPage {
+        RenderedPage {
+            slug: slug.to_string(),
+            html: html.to_string(),
+            source: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_links_reports_missing_files_and_anchors() {
+        let pages = vec![
+            page(
+                "index",
+                r##"<a href="/posts/hello/">ok</a>
+                <a href="posts/hello/#intro">ok</a>
+                <a href="/posts/hello/#missing">bad anchor</a>
+                <a href="/nope/">bad file</a>
+                <img src="/static/img/logo.png">
+                <a href="https://example.org/nope">external</a>
+                <a href="#top">bad self anchor</a>"##,
+            ),
+            page(
+                "posts/hello",
+                r#"<h2 id="intro">Intro</h2><a href="../../">home</a>"#,
+            ),
+        ];
+        let mut files = BTreeSet::new();
+        files.insert("index.html".to_string());
+        files.insert("posts/hello/index.html".to_string());
+
+        let broken = check_links(&pages, &files, &SiteConfig::default());
+        let urls: Vec<&str> = broken.iter().map(|b| b.url.as_str()).collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "/posts/hello/#missing",
+                "/nope/",
+                "/static/img/logo.png",
+                "#top"
+            ]
+        );
+        assert!(broken.iter().all(|b| b.page == "index.html"));
+    }
+}
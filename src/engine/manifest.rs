@@ -0,0 +1,231 @@
+use crate::engine::{output_file, theme, utils};
+use crate::models::{ProjectPaths, SiteConfig};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Component, Path};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Files the last build wrote to dist/, kept in `.ferropress-cache/` so the next
+/// build can remove outputs that are no longer produced instead of wiping dist/.
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    /// Relative to dist/, `/`-separated
+    files: BTreeSet<String>,
+}
+
+/// Every file a build writes, relative to dist/: the pages with `slugs`,
+/// copied static files and the Tailwind stylesheet.
+pub fn output_files<'a>(
+    paths: &ProjectPaths,
+    config: &SiteConfig,
+    slugs: impl IntoIterator<Item = &'a str>,
+) -> Result<BTreeSet<String>> {
+    let mut files: BTreeSet<String> = slugs.into_iter().map(output_file).collect();
+
+    let dist_static = paths.dist_static();
+    let static_prefix = dist_static
+        .strip_prefix(&paths.dist)
+        .unwrap_or(&dist_static)
+        .to_string_lossy()
+        .replace('\\', "/");
+    for relative in theme::layered_files(&theme::static_dirs(paths, config))?.into_keys() {
+        if !relative.starts_with("css/") {
+            files.insert(format!("{}/{}", static_prefix, relative));
+        }
+    }
+
+    let css = paths.output_css_file();
+    let css = css
+        .strip_prefix(&paths.dist)
+        .context("Stylesheet is written outside of dist/")?;
+    files.insert(css.to_string_lossy().replace('\\', "/"));
+
+    Ok(files)
+}
+
+/// Delete the files the previous build wrote that aren't in `outputs`, then
+/// record `outputs` for the next build. Without a manifest, every file already
+/// in dist/ counts as a previous output. Returns how many files were removed.
+pub fn prune(paths: &ProjectPaths, outputs: &BTreeSet<String>) -> Result<usize> {
+    let manifest_path = paths.cache.join(MANIFEST_FILE);
+    let previous = match fs::read(&manifest_path) {
+        Ok(bytes) => serde_json::from_slice::<Manifest>(&bytes)
+            .map(|manifest| manifest.files)
+            .unwrap_or_default(),
+        Err(_) if paths.dist.exists() => utils::relative_files(&paths.dist)?.into_keys().collect(),
+        Err(_) => BTreeSet::new(),
+    };
+
+    let mut removed = 0;
+    for stale in previous.difference(outputs) {
+        // never follow a hand-edited manifest outside of dist/
+        let relative = Path::new(stale);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            continue;
+        }
+
+        let path = paths.dist.join(relative);
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to remove stale file {:?}", path));
+            }
+        }
+        remove_empty_parents(&paths.dist, &path);
+    }
+
+    fs::create_dir_all(&paths.cache)
+        .with_context(|| format!("Failed to create cache directory {:?}", paths.cache))?;
+    let manifest = Manifest {
+        files: outputs.clone(),
+    };
+    let json =
+        serde_json::to_vec_pretty(&manifest).context("Failed to serialize build manifest")?;
+    fs::write(&manifest_path, json)
+        .with_context(|| format!("Failed to write build manifest {:?}", manifest_path))?;
+
+    Ok(removed)
+}
+
+/// Write `contents` unless the file already holds exactly that, so unchanged
+/// outputs keep their mtime. Returns whether the file was written.
+pub fn write_if_changed(path: &Path, contents: &[u8]) -> Result<bool> {
+    if fs::read(path).is_ok_and(|existing| existing == contents) {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(true)
+}
+
+/// [`write_if_changed`] for copying a file.
+pub fn copy_if_changed(src: &Path, dst: &Path) -> Result<bool> {
+    let same_len = match (fs::metadata(src), fs::metadata(dst)) {
+        (Ok(src), Ok(dst)) => src.len() == dst.len(),
+        _ => false,
+    };
+    if same_len {
+        let contents = fs::read(src).with_context(|| format!("Failed to read {:?}", src))?;
+        return write_if_changed(dst, &contents);
+    }
+
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+    fs::copy(src, dst).with_context(|| format!("Failed to copy {:?} to {:?}", src, dst))?;
+    Ok(true)
+}
+
+/// Remove directories left empty by a deleted file, up to (not including) `root`.
+fn remove_empty_parents(root: &Path, file: &Path) {
+    let mut dir = file.parent();
+    while let Some(current) = dir {
+        if current == root || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::tempdir;
+
+    fn outputs(files: &[&str]) -> BTreeSet<String> {
+        files.iter().map(|file| file.to_string()).collect()
+    }
+
+    fn write(paths: &ProjectPaths, relative: &str) {
+        write_if_changed(&paths.dist.join(relative), relative.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_prune_removes_stale_outputs_and_their_empty_directories() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        for file in ["index.html", "posts/old/index.html", "posts/new/index.html"] {
+            write(&paths, file);
+        }
+        let first = outputs(&["index.html", "posts/old/index.html", "posts/new/index.html"]);
+        assert_eq!(prune(&paths, &first).unwrap(), 0);
+
+        let second = outputs(&["index.html", "posts/new/index.html"]);
+        assert_eq!(prune(&paths, &second).unwrap(), 1);
+
+        assert!(!paths.dist.join("posts/old").exists());
+        assert!(paths.dist.join("posts/new/index.html").is_file());
+    }
+
+    #[test]
+    fn test_prune_ignores_manifest_entries_outside_dist() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        fs::create_dir_all(&paths.cache).unwrap();
+        fs::write(dir.path().join("site.toml"), "keep me").unwrap();
+        fs::write(
+            paths.cache.join(MANIFEST_FILE),
+            r#"{"files": ["../site.toml", "/etc/hostname"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(prune(&paths, &BTreeSet::new()).unwrap(), 0);
+        assert!(dir.path().join("site.toml").is_file());
+    }
+
+    #[test]
+    fn test_missing_manifest_treats_dist_as_previous_and_corrupt_one_as_empty() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        write(&paths, "index.html");
+        write(&paths, "leftover.html");
+
+        // no manifest yet: whatever is in dist/ came from an earlier build
+        assert_eq!(prune(&paths, &outputs(&["index.html"])).unwrap(), 1);
+        assert!(!paths.dist.join("leftover.html").exists());
+
+        // a corrupt manifest removes nothing and is replaced
+        write(&paths, "stray.html");
+        fs::write(paths.cache.join(MANIFEST_FILE), "{not json").unwrap();
+        assert_eq!(prune(&paths, &outputs(&["index.html"])).unwrap(), 0);
+        assert!(paths.dist.join("stray.html").is_file());
+        assert_eq!(prune(&paths, &outputs(&["index.html"])).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_unchanged_outputs_keep_their_mtime() {
+        let dir = tempdir().unwrap();
+        let page = dir.path().join("index.html");
+        let copy = dir.path().join("copy.html");
+        assert!(write_if_changed(&page, b"<p>hi</p>").unwrap());
+        assert!(copy_if_changed(&page, &copy).unwrap());
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        for file in [&page, &copy] {
+            let file = fs::File::options().write(true).open(file).unwrap();
+            file.set_modified(old).unwrap();
+        }
+
+        assert!(!write_if_changed(&page, b"<p>hi</p>").unwrap());
+        assert!(!copy_if_changed(&page, &copy).unwrap());
+        for file in [&page, &copy] {
+            assert_eq!(fs::metadata(file).unwrap().modified().unwrap(), old);
+        }
+
+        assert!(write_if_changed(&page, b"<p>bye</p>").unwrap());
+        assert_eq!(fs::read(&page).unwrap(), b"<p>bye</p>");
+    }
+}
@@ -0,0 +1,259 @@
+use crate::engine::html::{self, Token};
+
+/// Elements whose text is whitespace-sensitive and copied verbatim.
+const PREFORMATTED: [&str; 2] = ["pre", "textarea"];
+
+/// Elements that break the line, so whitespace right inside or around their tags
+/// never renders and can be dropped.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "br",
+    "caption",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "meta",
+    "nav",
+    "ol",
+    "option",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Shrink rendered HTML without changing how it displays: comments are dropped,
+/// runs of whitespace collapse to one space (or none next to block-level tags)
+/// and tags lose redundant whitespace. `<pre>`, `<textarea>`, scripts and styles
+/// are copied untouched.
+pub fn minify_html(source: &str) -> String {
+    let tokens = html::tokenize(source);
+    let mut out = String::with_capacity(source.len());
+    let mut preformatted = 0usize;
+    // a dropped comment can leave two collapsed spaces next to each other
+    let mut ends_in_space = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Start(tag) => {
+                out.push_str(&minify_tag(&source[tag.span.clone()]));
+                if PREFORMATTED.contains(&tag.name.as_str()) && !tag.self_closing {
+                    preformatted += 1;
+                }
+                ends_in_space = false;
+            }
+            Token::End { name, .. } => {
+                if PREFORMATTED.contains(&name.as_str()) {
+                    preformatted = preformatted.saturating_sub(1);
+                }
+                out.push_str("</");
+                out.push_str(name);
+                out.push('>');
+                ends_in_space = false;
+            }
+            Token::Text(text) if preformatted > 0 => {
+                out.push_str(text);
+                ends_in_space = false;
+            }
+            Token::Text(text) => {
+                let mut text = collapse_whitespace(text);
+                if is_block_boundary(i.checked_sub(1).and_then(|i| tokens.get(i))) {
+                    text = text.trim_start().to_string();
+                }
+                if is_block_boundary(tokens.get(i + 1)) {
+                    text = text.trim_end().to_string();
+                }
+                let text = match text.strip_prefix(' ') {
+                    Some(rest) if ends_in_space => rest,
+                    _ => text.as_str(),
+                };
+                if !text.is_empty() {
+                    out.push_str(text);
+                    ends_in_space = text.ends_with(' ');
+                }
+            }
+            Token::RawText(text) => {
+                out.push_str(text);
+                ends_in_space = false;
+            }
+            // conditional comments (`<!--[if IE]>`) still do something
+            Token::Other(raw) if raw.starts_with("<!--") && !raw.starts_with("<!--[") => {}
+            Token::Other(raw) => {
+                out.push_str(raw);
+                ends_in_space = false;
+            }
+        }
+    }
+
+    out
+}
+
+/// Whitespace next to `token` doesn't render: it's a block-level tag, a doctype,
+/// or the start or end of the document.
+fn is_block_boundary(token: Option<&Token>) -> bool {
+    match token {
+        None => true,
+        Some(Token::Start(tag)) => BLOCK_ELEMENTS.contains(&tag.name.as_str()),
+        Some(Token::End { name, .. }) => BLOCK_ELEMENTS.contains(&name.as_str()),
+        Some(Token::Other(raw)) => !raw.starts_with("<!--"),
+        Some(Token::Text(_) | Token::RawText(_)) => false,
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+/// Collapse whitespace between attributes and drop it before `>` or `/>`.
+/// Quoted attribute values are copied as is.
+fn minify_tag(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+    let mut after_equals = false;
+    let mut in_unquoted_value = false;
+
+    while let Some((i, c)) = chars.next() {
+        if c.is_ascii_whitespace() {
+            while chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+            let rest = chars.peek().map_or("", |&(j, _)| &raw[j..]);
+            // `src=x />` keeps its space, or the `/` would become part of the value
+            let closes = rest.starts_with('>') || (rest.starts_with("/>") && !in_unquoted_value);
+            if !after_equals && !closes {
+                out.push(' ');
+            }
+            in_unquoted_value = false;
+            continue;
+        }
+
+        if after_equals && (c == '"' || c == '\'') {
+            let end = raw[i + 1..].find(c).map_or(raw.len(), |j| i + 1 + j + 1);
+            out.push_str(&raw[i..end]);
+            while chars.next_if(|&(j, _)| j < end).is_some() {}
+        } else {
+            in_unquoted_value |= after_equals;
+            out.push(c);
+        }
+        after_equals = c == '=';
+        if after_equals {
+            // drop the space before `=` too: `key = "v"` -> `key="v"`
+            if out[..out.len() - 1].ends_with(' ') {
+                out.remove(out.len() - 2);
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_keeps_preformatted_and_raw_text() {
+        let page = "<!DOCTYPE html>\n<html lang=\"en\">\n  <head>\n    <title> Hi </title>\n    \
+            <style>\n  body { color: red; }\n</style>\n  </head>\n  <body class = \"a  b\" >\n    \
+            <!-- nav -->\n    <p>Some   <em>inline</em>\n    text</p>\n    \
+            <pre><code>fn main() {\n    let x = 1;\n}</code></pre>\n    \
+            <script>\n  if (a < b) { x = \"  \"; }\n</script>\n  </body>\n</html>\n";
+
+        assert_eq!(
+            minify_html(page),
+            "<!DOCTYPE html><html lang=\"en\"><head><title>Hi</title>\
+            <style>\n  body { color: red; }\n</style></head><body class=\"a  b\">\
+            <p>Some <em>inline</em> text</p>\
+            <pre><code>fn main() {\n    let x = 1;\n}</code></pre>\
+            <script>\n  if (a < b) { x = \"  \"; }\n</script></body></html>"
+        );
+    }
+
+    #[test]
+    fn test_minify_tag_attributes_and_self_closing_tags() {
+        let cases = [
+            ("<img src=x />", "<img src=x />"),
+            ("<img src=x/>", "<img src=x/>"),
+            ("<img src=\"x\" />", "<img src=\"x\"/>"),
+            ("<br />", "<br/>"),
+            ("<input disabled />", "<input disabled/>"),
+            (
+                "<input value = 'a  b'  disabled >",
+                "<input value='a  b' disabled>",
+            ),
+            (
+                "<div data-x=a\n   class=\"b\">",
+                "<div data-x=a class=\"b\">",
+            ),
+            (
+                "<a title='say \"hi\"' href=/x>",
+                "<a title='say \"hi\"' href=/x>",
+            ),
+        ];
+
+        for (tag, expected) in cases {
+            assert_eq!(minify_tag(tag), expected, "{tag}");
+        }
+    }
+
+    #[test]
+    fn test_minify_drops_whitespace_around_lists_and_form_parts() {
+        let page = "<ul>\n  <li>a</li>\n</ul>\n<table>\n  <caption> Totals </caption>\n</table>\n\
+            <fieldset>\n  <legend> Pick </legend>\n  <select>\n    <option>1</option>\n  </select>\n</fieldset>";
+
+        assert_eq!(
+            minify_html(page),
+            "<ul><li>a</li></ul><table><caption>Totals</caption></table>\
+            <fieldset><legend>Pick</legend><select><option>1</option></select></fieldset>"
+        );
+    }
+}
@@ -1,47 +1,138 @@
+use crate::engine::cache::PostCache;
+use crate::engine::diagnostics::{self, Diagnostic};
+use crate::engine::render::markdown_to_html;
 use crate::engine::utils;
 use crate::models::{Post, PostMetadata};
 use anyhow::{Context, Result};
 use gray_matter::Matter;
+use rayon::prelude::*;
 use std::fs;
 use std::path::Path;
 
 pub fn parse_post(path: &Path, prefix: &Path) -> Result<Post> {
     let raw =
         fs::read_to_string(path).with_context(|| format!("Failed to read file at {:?}", path))?;
+    parse_post_source(path, prefix, &raw)
+}
+
+/// Parse a post from its already-read contents and convert its Markdown body.
+pub fn parse_post_source(path: &Path, prefix: &Path, raw: &str) -> Result<Post> {
+    let mut post = parse_front_matter_source(path, prefix, raw)?;
+    post.html = markdown_to_html(&post.content);
+    Ok(post)
+}
 
+/// [`parse_post`] without the Markdown conversion, for callers that only need
+/// the metadata (`html` is left empty).
+pub fn parse_front_matter(path: &Path, prefix: &Path) -> Result<Post> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read file at {:?}", path))?;
+    parse_front_matter_source(path, prefix, &raw)
+}
+
+fn parse_front_matter_source(path: &Path, prefix: &Path, raw: &str) -> Result<Post> {
     let matter = Matter::<gray_matter::engine::YAML>::new();
-    let result = matter
-        .parse::<PostMetadata>(&raw)
-        .with_context(|| format!("Failed to parse front matter in {:?}", path))?;
+    let result = matter.parse::<serde_json::Value>(raw).map_err(|e| {
+        let message = e.to_string();
+        match diagnostics::line_and_column(&message) {
+            // YAML positions count from the line after the opening `---`
+            Some((line, column)) => {
+                let reason = message.split(" at byte ").next().unwrap_or(&message);
+                let reason = reason.trim_start_matches("Deserialize error: ");
+                Diagnostic::new(reason, path, raw, line + 1, column).into()
+            }
+            None => anyhow::Error::new(e),
+        }
+    });
+    let result = result.with_context(|| format!("Failed to parse front matter in {:?}", path))?;
 
-    let metadata = result
+    let data = result
         .data
         .with_context(|| format!("Invalid post front matter in {:?}", path))?;
 
+    let metadata: PostMetadata = serde_path_to_error::deserialize(data).map_err(|e| {
+        let key = e.path().iter().next().map(|segment| segment.to_string());
+        let (line, column) = key
+            .and_then(|key| diagnostics::locate_front_matter_key(raw, &key))
+            .unwrap_or((1, 1));
+        anyhow::Error::from(Diagnostic::new(
+            e.inner().to_string(),
+            path,
+            raw,
+            line,
+            column,
+        ))
+        .context(format!("Invalid post front matter in {:?}", path))
+    })?;
+
     let slug = utils::Slugify::from_path(path, prefix)?;
 
     Ok(Post {
         metadata,
+        html: String::new(),
         content: result.content,
         slug,
+        source: path.to_path_buf(),
+    })
+}
+
+/// Every post's front matter, without converting any Markdown.
+pub fn parse_all_front_matter(content_dir: &Path) -> Result<Vec<Post>> {
+    first_error(collect_posts(content_dir, |path| {
+        parse_front_matter(path, content_dir)
+    })?)
+}
+
+/// Every post, reusing unchanged posts from the build cache.
+pub fn parse_all_posts_cached(content_dir: &Path, cache: Option<&PostCache>) -> Result<Vec<Post>> {
+    first_error(parse_posts_collecting_errors(content_dir, cache)?)
+}
+
+fn first_error((posts, errors): (Vec<Post>, Vec<anyhow::Error>)) -> Result<Vec<Post>> {
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(posts),
+    }
+}
+
+/// Like [`parse_all_posts_cached`], but keeps going past posts that fail to parse
+/// and returns their errors alongside the posts that did parse.
+pub fn parse_posts_collecting_errors(
+    content_dir: &Path,
+    cache: Option<&PostCache>,
+) -> Result<(Vec<Post>, Vec<anyhow::Error>)> {
+    collect_posts(content_dir, |path| match cache {
+        Some(cache) => cache.parse_post(path, content_dir),
+        None => parse_post(path, content_dir),
     })
 }
 
-pub fn parse_all_posts(content_dir: &Path) -> Result<Vec<Post>> {
+fn collect_posts(
+    content_dir: &Path,
+    parse: impl Fn(&Path) -> Result<Post> + Sync,
+) -> Result<(Vec<Post>, Vec<anyhow::Error>)> {
     let posts_dir = content_dir.join("posts");
     let mut posts = Vec::new();
+    let mut errors = Vec::new();
 
     if !posts_dir.exists() {
-        return Ok(posts);
+        return Ok((posts, errors));
     }
 
-    for entry in utils::walk_dir(&posts_dir, "md")? {
-        let post = parse_post(&entry, content_dir)?;
-        posts.push(post);
+    // parse in parallel; collecting keeps the sorted file order, so output is deterministic
+    let results: Vec<Result<Post>> = utils::walk_dir(&posts_dir, "md")?
+        .par_iter()
+        .map(|entry| parse(entry))
+        .collect();
+    for result in results {
+        match result {
+            Ok(post) => posts.push(post),
+            Err(e) => errors.push(e),
+        }
     }
 
-    posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
-    Ok(posts)
+    posts.sort_by_key(|p| std::cmp::Reverse(p.metadata.date));
+    Ok((posts, errors))
 }
 
 #[cfg(test)]
@@ -80,4 +171,37 @@ This is the body."
         assert_eq!(post.slug, "posts/hello-world");
         assert_eq!(post.content.trim(), "This is the body.");
     }
+
+    #[test]
+    fn test_front_matter_only_skips_markdown() {
+        let dir = tempdir().unwrap();
+        let posts_dir = dir.path().join("posts");
+        fs::create_dir(&posts_dir).unwrap();
+        fs::write(
+            posts_dir.join("a.md"),
+            "---\ntitle: A\ndate: 2026-01-01\n---\n*Hi*",
+        )
+        .unwrap();
+
+        let posts = parse_all_front_matter(dir.path()).unwrap();
+        assert_eq!(posts[0].metadata.title, "A");
+        assert_eq!(posts[0].html, "");
+
+        let posts = parse_all_posts_cached(dir.path(), None).unwrap();
+        assert_eq!(posts[0].html, "<p><em>Hi</em></p>\n");
+    }
+
+    #[test]
+    fn test_invalid_date_points_at_front_matter_line() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("bad.md");
+        fs::write(&file_path, "---\ntitle: Bad\ndate: 2025-13-01\n---\nBody").unwrap();
+
+        let err = parse_post(&file_path, dir.path()).unwrap_err();
+        let diagnostic = err.downcast_ref::<Diagnostic>().unwrap();
+
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 7));
+        assert_eq!(diagnostic.snippet, "date: 2025-13-01");
+        assert!(diagnostic.message.contains("invalid date '2025-13-01'"));
+    }
 }
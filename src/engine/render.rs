@@ -1,93 +1,282 @@
-use crate::engine::utils;
-use crate::models::{Post, ProjectPaths, RenderedPage, SiteConfig};
+use crate::engine::fingerprint::AssetManifest;
+use crate::engine::theme;
+use crate::engine::{diagnostics, filters, functions, utils};
+use crate::models::{CollectionEntry, Post, RenderedPage, SiteConfig};
 use anyhow::{Context, Result};
-use std::path::Path;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 use tera::Tera;
 
+/// One output the renderer can produce.
+pub enum PageRef<'a> {
+    Post(&'a Post),
+    /// A template under `pages/`
+    Page(&'a str),
+    Collection(&'a CollectionEntry),
+}
+
+impl PageRef<'_> {
+    /// Template the page is rendered with
+    pub fn template(&self) -> &str {
+        match self {
+            PageRef::Post(_) => "post.html",
+            PageRef::Page(name) => name,
+            PageRef::Collection(entry) => &entry.template,
+        }
+    }
+
+    /// Output slug, e.g. "about" for "pages/about.html"
+    pub fn slug(&self) -> Result<String> {
+        match self {
+            PageRef::Post(post) => Ok(post.slug.clone()),
+            PageRef::Page(name) => utils::Slugify::from_path(Path::new(name), Path::new("pages")),
+            PageRef::Collection(entry) => Ok(entry.slug.clone()),
+        }
+    }
+}
+
 pub struct Renderer {
     pub tera: Tera,
+    /// Template name (e.g. "pages/about.html") -> file it was loaded from
+    pub templates: BTreeMap<String, PathBuf>,
+    /// Fingerprinted static files `asset_url` resolves to (empty outside production builds)
+    assets: AssetManifest,
 }
 
 impl Renderer {
-    pub fn new(template_dir: &Path) -> Result<Self> {
-        let tera = Tera::new(&format!("{}/**/*", template_dir.display()))
-            .context("Failed to initialize Tera templates")?;
-        Ok(Self { tera })
+    /// Load templates from layered directories; a later directory overrides
+    /// any same-named template from an earlier one (project over theme).
+    pub fn new(template_dirs: &[PathBuf], assets: AssetManifest) -> Result<Self> {
+        Self::from_templates(theme::layered_files(template_dirs)?, assets)
+    }
+
+    /// Compile exactly `templates` (name -> file), e.g. a set with broken ones left out.
+    pub fn from_templates(
+        templates: BTreeMap<String, PathBuf>,
+        assets: AssetManifest,
+    ) -> Result<Self> {
+        let mut tera = Tera::default();
+        tera.add_template_files(
+            templates
+                .iter()
+                .map(|(name, path)| (path, Some(name.as_str()))),
+        )
+        .map_err(|e| diagnostics::tera_error(e, &templates))
+        .context("Failed to initialize Tera templates")?;
+        filters::register(&mut tera);
+
+        Ok(Self {
+            tera,
+            templates,
+            assets,
+        })
+    }
+
+    /// Re-read and recompile the `names` templates in place, keeping every other
+    /// compiled template. Tera rebuilds inheritance chains, so layouts can be reloaded too.
+    pub fn reload_templates(&mut self, names: &BTreeSet<String>) -> Result<()> {
+        let files = names
+            .iter()
+            .filter_map(|name| Some((self.templates.get(name)?, Some(name.as_str()))));
+        self.tera
+            .add_template_files(files)
+            .map_err(|e| diagnostics::tera_error(e, &self.templates))
+            .context("Failed to reload templates")
     }
 
-    pub fn render_all_posts(
+    /// Expose `get_page`, `get_section`, `get_taxonomy`, `url_for`, and
+    /// `asset_url`/`asset_integrity` resolving through the renderer's assets.
+    pub fn register_site_functions(&mut self, posts: &[Post], config: &SiteConfig) {
+        let pages: Vec<String> = self
+            .page_templates()
+            .filter_map(|(name, _)| PageRef::Page(name).slug().ok())
+            .collect();
+        functions::register(
+            &mut self.tera,
+            functions::SiteIndex::new(posts, &pages, config),
+        );
+        functions::register_asset_url(&mut self.tera, config, self.assets.clone());
+    }
+
+    /// Render every post, page template and collection entry. Each output is
+    /// rendered independently so one broken template doesn't hide the others.
+    pub fn render_all(&self, posts: &[Post], config: &SiteConfig) -> Vec<Result<RenderedPage>> {
+        self.render_where(posts, config, |_| true)
+    }
+
+    /// Render only the outputs `wanted` selects, in parallel.
+    pub fn render_where(
         &self,
         posts: &[Post],
         config: &SiteConfig,
-    ) -> Result<Vec<RenderedPage>> {
-        let mut rendered = Vec::new();
+        wanted: impl Fn(&PageRef) -> bool,
+    ) -> Vec<Result<RenderedPage>> {
+        let selected: Vec<PageRef> = self
+            .pages(posts, config)
+            .filter(|page| wanted(page))
+            .collect();
 
-        for post in posts {
-            let mut ctx = config.base_context();
-            ctx.insert("post", &post.metadata);
-            ctx.insert("content", &markdown_to_html(&post.content));
+        // Markdown conversion and Tera rendering run across the thread pool;
+        // results come back in the same order as `selected`
+        selected
+            .into_par_iter()
+            .map(|page| match page {
+                PageRef::Post(post) => self.render_post(post, config),
+                PageRef::Page(name) => self.render_page(name, &self.templates[name], posts, config),
+                PageRef::Collection(entry) => self.render_collection_entry(entry, config),
+            })
+            .collect()
+    }
 
-            let html = self.tera.render("post.html", &ctx).with_context(|| {
-                format!("Failed to render post template for slug: {}", post.slug)
-            })?;
+    /// Slugs of every output [`Renderer::render_all`] would produce.
+    pub fn slugs(&self, posts: &[Post], config: &SiteConfig) -> Result<Vec<String>> {
+        self.pages(posts, config).map(|page| page.slug()).collect()
+    }
 
-            rendered.push(RenderedPage {
-                slug: post.slug.clone(),
-                html,
-            });
-        }
-        Ok(rendered)
+    fn pages<'a>(
+        &'a self,
+        posts: &'a [Post],
+        config: &'a SiteConfig,
+    ) -> impl Iterator<Item = PageRef<'a>> {
+        let post_refs = posts.iter().map(PageRef::Post);
+        let page_refs = self.page_templates().map(|(name, _)| PageRef::Page(name));
+        let entry_refs = config
+            .collection_entries
+            .values()
+            .flatten()
+            .map(PageRef::Collection);
+
+        post_refs.chain(page_refs).chain(entry_refs)
+    }
+
+    fn render_post(&self, post: &Post, config: &SiteConfig) -> Result<RenderedPage> {
+        let mut ctx = config.base_context();
+        ctx.insert("post", &post.metadata);
+        ctx.insert("slug", &post.slug);
+        ctx.insert("content", &post.html);
+
+        let html = self
+            .tera
+            .render("post.html", &ctx)
+            .map_err(|e| diagnostics::tera_error(e, &self.templates))
+            .with_context(|| format!("Failed to render post template for slug: {}", post.slug))?;
+
+        Ok(RenderedPage {
+            slug: post.slug.clone(),
+            html,
+            source: post.source.clone(),
+        })
     }
 
-    pub fn render_all_pages(
+    fn render_page(
         &self,
+        template_name: &str,
+        path: &Path,
         posts: &[Post],
-        paths: &ProjectPaths,
         config: &SiteConfig,
-    ) -> Result<Vec<RenderedPage>> {
-        let mut rendered = Vec::new();
-        let pages_dir = paths.templates.join("pages");
+    ) -> Result<RenderedPage> {
+        let slug = PageRef::Page(template_name).slug()?;
 
-        if !pages_dir.exists() {
-            return Ok(rendered);
-        }
+        let mut ctx = config.base_context();
+        ctx.insert("posts", posts);
 
-        // Fixed: walk_dir returns a Result, so we need to handle it
-        for entry in utils::walk_dir(&pages_dir, "html")? {
-            // Slug is the output path (e.g., "about")
-            let slug = utils::Slugify::from_path(&entry, &pages_dir)?;
+        let html = self
+            .tera
+            .render(template_name, &ctx)
+            .map_err(|e| diagnostics::tera_error(e, &self.templates))
+            .with_context(|| format!("Failed to render page template: {:?}", path))?;
 
-            let mut ctx = config.base_context();
-            ctx.insert("posts", posts);
+        Ok(RenderedPage {
+            slug,
+            html,
+            source: path.to_path_buf(),
+        })
+    }
 
-            // template_name is the internal Tera key (e.g., "pages/about.html")
-            let template_path = entry
-                .strip_prefix(&paths.templates)
-                .with_context(|| format!("Path {:?} is outside of template directory", entry))?;
+    /// Templates under `pages/` each render to a standalone page.
+    fn page_templates(&self) -> impl Iterator<Item = (&str, &PathBuf)> {
+        self.templates
+            .iter()
+            .filter(|(name, _)| name.starts_with("pages/") && name.ends_with(".html"))
+            .map(|(name, path)| (name.as_str(), path))
+    }
 
-            let template_name = template_path.to_string_lossy();
+    fn render_collection_entry(
+        &self,
+        entry: &CollectionEntry,
+        config: &SiteConfig,
+    ) -> Result<RenderedPage> {
+        let mut ctx = config.base_context();
+        ctx.insert("record", &entry.record);
+        ctx.insert("slug", &entry.slug);
 
-            let html = self
-                .tera
-                .render(&template_name, &ctx)
-                .with_context(|| format!("Failed to render page template: {:?}", entry))?;
+        let html = self
+            .tera
+            .render(&entry.template, &ctx)
+            .map_err(|e| diagnostics::tera_error(e, &self.templates))
+            .with_context(|| {
+                format!(
+                    "Failed to render collection template {} for slug: {}",
+                    entry.template, entry.slug
+                )
+            })?;
+
+        Ok(RenderedPage {
+            slug: entry.slug.clone(),
+            html,
+            source: self
+                .templates
+                .get(&entry.template)
+                .cloned()
+                .unwrap_or_else(|| PathBuf::from(&entry.template)),
+        })
+    }
+}
+
+/// Parse each template on its own so every syntax error is reported, not just the
+/// first one Tera hits while loading the whole set. Keyed by template name.
+pub fn template_syntax_errors(
+    templates: &BTreeMap<String, PathBuf>,
+) -> BTreeMap<String, anyhow::Error> {
+    let mut errors = BTreeMap::new();
 
-            rendered.push(RenderedPage { slug, html })
+    for (name, path) in templates {
+        let result = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {:?}", path))
+            .and_then(|raw| {
+                let single = BTreeMap::from([(name.clone(), path.clone())]);
+                // named the way Tera names the templates it fails to load
+                tera::Template::new(name, Some(path.to_string_lossy().to_string()), &raw)
+                    .map_err(|e| tera::Error::chain(format!("Failed to parse '{}'", name), e))
+                    .map_err(|e| diagnostics::tera_error(e, &single))
+                    .with_context(|| format!("Invalid template syntax in {:?}", path))
+            });
+
+        if let Err(e) = result {
+            errors.insert(name.clone(), e);
         }
-        Ok(rendered)
     }
+
+    errors
 }
 
-fn markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{Options, Parser, html};
+/// Extensions enabled for every Markdown conversion. Part of the build cache key.
+pub(crate) fn markdown_options() -> pulldown_cmark::Options {
+    use pulldown_cmark::Options;
 
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_FOOTNOTES);
+    options
+}
+
+pub(crate) fn markdown_to_html(markdown: &str) -> String {
+    use pulldown_cmark::{Parser, html};
 
-    let parser = Parser::new_ext(markdown, options);
+    let parser = Parser::new_ext(markdown, markdown_options());
 
     let mut html_output = String::new();
     html::push_html(&mut html_output, parser);
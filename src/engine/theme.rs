@@ -0,0 +1,164 @@
+use crate::engine::utils;
+use crate::models::{ProjectPaths, SiteConfig, ThemeManifest};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Load site.toml, merging the active theme's `[config]` defaults underneath it.
+pub fn load_site_config(paths: &ProjectPaths) -> Result<SiteConfig> {
+    let mut table = SiteConfig::load_table(&paths.config)?;
+
+    if let Some(name) = table.get("theme").and_then(|v| v.as_str()) {
+        validate_theme_name(name).context("Invalid `theme` in site.toml")?;
+        let theme_dir = paths.theme_dir(name);
+        if !theme_dir.is_dir() {
+            anyhow::bail!(
+                "Theme '{}' not found (expected directory {})",
+                name,
+                theme_dir.display()
+            );
+        }
+
+        let manifest = ThemeManifest::load(&theme_dir)?;
+        merge_defaults(&mut table, manifest.config);
+    }
+
+    SiteConfig::from_table(table)
+}
+
+/// Theme names become directories under themes/, so only allow plain names
+/// that can't point anywhere else.
+pub fn validate_theme_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        anyhow::bail!(
+            "Invalid theme name '{}': use letters, digits, '-' and '_' only",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Fill in keys missing from `table` with `defaults`, recursing into nested tables.
+fn merge_defaults(table: &mut toml::Table, defaults: toml::Table) {
+    for (key, default) in defaults {
+        match (table.get_mut(&key), default) {
+            (None, default) => {
+                table.insert(key, default);
+            }
+            (Some(toml::Value::Table(existing)), toml::Value::Table(default)) => {
+                merge_defaults(existing, default);
+            }
+            // the project's own value wins
+            (Some(_), _) => {}
+        }
+    }
+}
+
+/// Template directories in precedence order: theme first, project last (project wins).
+pub fn template_dirs(paths: &ProjectPaths, config: &SiteConfig) -> Vec<PathBuf> {
+    layered_dirs(paths, config, "templates", &paths.templates)
+}
+
+/// Static directories in precedence order: theme first, project last (project wins).
+pub fn static_dirs(paths: &ProjectPaths, config: &SiteConfig) -> Vec<PathBuf> {
+    layered_dirs(paths, config, "static", &paths.static_files)
+}
+
+fn layered_dirs(
+    paths: &ProjectPaths,
+    config: &SiteConfig,
+    sub_dir: &str,
+    project_dir: &Path,
+) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(theme) = &config.theme {
+        dirs.push(paths.theme_dir(theme).join(sub_dir));
+    }
+    dirs.push(project_dir.to_path_buf());
+    dirs
+}
+
+/// Merge the files of several layered directories into one view keyed by relative path
+/// (always `/`-separated). Later directories override earlier ones.
+pub fn layered_files(dirs: &[PathBuf]) -> Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+
+    for dir in dirs.iter().filter(|d| d.is_dir()) {
+        files.extend(utils::relative_files(dir)?);
+    }
+
+    Ok(files)
+}
+
+/// Tailwind entry point: the project's `static/css/input.css`, falling back to the theme's.
+pub fn input_css_file(paths: &ProjectPaths, config: &SiteConfig) -> PathBuf {
+    let project_css = paths.input_css_file();
+    if project_css.exists() {
+        return project_css;
+    }
+
+    static_dirs(paths, config)
+        .into_iter()
+        .map(|dir| dir.join("css").join("input.css"))
+        .find(|css| css.exists())
+        .unwrap_or(project_css)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_merge_defaults_keeps_project_values() {
+        let mut table: toml::Table =
+            toml::from_str("title = \"Mine\"\n[brand]\nprimary = \"red\"\n").unwrap();
+        let defaults: toml::Table = toml::from_str(
+            "title = \"Theme\"\nauthor = \"Ada\"\n[brand]\nprimary = \"blue\"\naccent = \"gold\"\n",
+        )
+        .unwrap();
+
+        merge_defaults(&mut table, defaults);
+
+        assert_eq!(table["title"].as_str(), Some("Mine"));
+        assert_eq!(table["author"].as_str(), Some("Ada"));
+        assert_eq!(table["brand"]["primary"].as_str(), Some("red"));
+        assert_eq!(table["brand"]["accent"].as_str(), Some("gold"));
+    }
+
+    #[test]
+    fn test_layered_files_prefers_later_dirs() {
+        let dir = tempdir().unwrap();
+        let (theme, project) = (dir.path().join("theme"), dir.path().join("project"));
+        fs::create_dir_all(theme.join("layouts")).unwrap();
+        fs::create_dir_all(project.join("layouts")).unwrap();
+        fs::write(theme.join("layouts/base.html"), "theme").unwrap();
+        fs::write(theme.join("post.html"), "theme").unwrap();
+        fs::write(project.join("layouts/base.html"), "project").unwrap();
+
+        let files =
+            layered_files(&[theme.clone(), project.clone(), dir.path().join("missing")]).unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            files["layouts/base.html"],
+            project.join("layouts/base.html")
+        );
+        assert_eq!(files["post.html"], theme.join("post.html"));
+    }
+
+    #[test]
+    fn test_rejects_theme_names_outside_themes_dir() {
+        let dir = tempdir().unwrap();
+        let paths = ProjectPaths::from_root(dir.path());
+        fs::write(&paths.config, "theme = \"../../x\"\n").unwrap();
+
+        let err = format!("{:#}", load_site_config(&paths).unwrap_err());
+        assert!(err.contains("Invalid theme name '../../x'"), "{err}");
+    }
+}
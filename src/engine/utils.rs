@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 pub struct Slugify;
@@ -18,6 +19,53 @@ impl Slugify {
             .trim_matches('/')
             .to_string())
     }
+
+    /// Slug for free text (titles, record fields): "Hello, World!" -> "hello-world"
+    pub fn from_text(text: &str) -> String {
+        let mut slug = String::with_capacity(text.len());
+        for c in text.trim().to_lowercase().chars() {
+            if c.is_alphanumeric() {
+                slug.push(c);
+            } else if !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        slug.trim_matches('-').to_string()
+    }
+}
+
+/// Replace `{{ name }}` (or `{{name}}`) placeholders with their values.
+pub fn fill_placeholders(text: &str, vars: &[(&str, &str)]) -> String {
+    let mut text = text.to_string();
+    for (name, value) in vars {
+        text = text
+            .replace(&format!("{{{{ {} }}}}", name), value)
+            .replace(&format!("{{{{{}}}}}", name), value);
+    }
+    text
+}
+
+/// Every file under `dir`, keyed by its path relative to `dir` (always `/`-separated).
+pub fn relative_files(dir: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+
+    for entry in walkdir::WalkDir::new(dir) {
+        let entry = entry.with_context(|| format!("Failed to walk {}", dir.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry
+            .path()
+            .strip_prefix(dir)
+            .with_context(|| format!("Path {:?} is outside of {:?}", entry.path(), dir))?
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        files.insert(relative, entry.path().to_path_buf());
+    }
+
+    Ok(files)
 }
 
 pub fn walk_dir(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
@@ -2,7 +2,7 @@ pub mod cmd;
 pub mod engine;
 pub mod models;
 
-use crate::models::ServeMode;
+use crate::models::{BuildOptions, ProjectPaths, ServeMode};
 use anyhow::{Context, Result};
 use clap::Parser;
 use cmd::{Cli, Commands};
@@ -23,27 +23,68 @@ async fn main() -> ExitCode {
 
 async fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Commands::Init {
-            path,
-            overwrite,
-            clean,
+        Commands::Init(args) => {
+            cmd::init::execute(args).await.context("init failed")?;
+        }
+        Commands::Build {
+            drafts,
+            future,
+            check_links,
+            a11y,
+            jobs,
+            compress,
+        } => {
+            cmd::build::execute(BuildOptions {
+                drafts,
+                future,
+                check_links,
+                a11y,
+                jobs,
+                compress,
+            })
+            .await
+            .context("build failed")?;
+        }
+        Commands::Check {
+            drafts,
+            future,
+            a11y,
+            jobs,
         } => {
-            cmd::init::execute(path, overwrite, clean)
+            cmd::check::execute(BuildOptions {
+                drafts,
+                future,
+                a11y,
+                jobs,
+                ..Default::default()
+            })
+            .await
+            .context("check failed")?;
+        }
+        Commands::New { target, title } => {
+            cmd::new::execute(target, title)
                 .await
-                .context("init failed")?;
+                .context("new failed")?;
         }
-        Commands::Build => {
-            cmd::build::execute().await.context("build failed")?;
+        Commands::List(args) => {
+            cmd::list::execute(args).await.context("list failed")?;
         }
         Commands::Preview => {
             cmd::serve::execute(ServeMode::Dev)
                 .await
                 .context("preview failed")?;
         }
-        Commands::Serve => {
-            cmd::serve::execute(ServeMode::Prod)
-                .await
-                .context("serve failed")?;
+        Commands::Serve { no_build, dir } => {
+            let served = if no_build || dir.is_some() {
+                let dir = dir.unwrap_or_else(|| ProjectPaths::default().dist);
+                cmd::serve::execute_existing(dir).await
+            } else {
+                cmd::serve::execute(ServeMode::Prod).await
+            };
+            served.context("serve failed")?;
+        }
+        Commands::Theme { command } => {
+            cmd::theme::execute(command).await.context("theme failed")?;
         }
     }
     Ok(())
@@ -1,11 +1,19 @@
+pub mod build;
+pub mod collection;
 pub mod content;
+pub mod lint;
 pub mod paths;
 pub mod render;
 pub mod serve;
 pub mod site;
+pub mod theme;
 
+pub use build::{BuildConfig, BuildOptions, CspConfig, CspMode};
+pub use collection::{CollectionConfig, CollectionEntry};
 pub use content::{Post, PostMetadata};
+pub use lint::{A11yConfig, Rule, Severity};
 pub use paths::ProjectPaths;
 pub use render::RenderedPage;
 pub use serve::ServeMode;
 pub use site::{NavbarLink, SiteConfig};
+pub use theme::ThemeManifest;
@@ -0,0 +1,174 @@
+use crate::models::Post;
+use chrono::Local;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// Options for a single render of the site.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Render posts marked `draft: true`
+    pub drafts: bool,
+    /// Render posts dated after today
+    pub future: bool,
+    /// Verify internal links and anchors once the site is rendered
+    pub check_links: bool,
+    /// Run the accessibility lint configured by `[a11y]` in site.toml
+    pub a11y: bool,
+    /// Worker threads for parsing, rendering and writing (one per core when unset)
+    pub jobs: Option<usize>,
+    /// Write `.gz` and `.br` variants of larger text outputs
+    pub compress: bool,
+}
+
+impl BuildOptions {
+    /// Whether `post` is part of this render: drafts only with `drafts` set,
+    /// scheduled posts only with `future` set.
+    pub fn includes(&self, post: &Post) -> bool {
+        (self.drafts || !post.metadata.draft)
+            && (self.future || !post.is_scheduled(Local::now().date_naive()))
+    }
+}
+
+/// `[build]` in site.toml: post-processing applied to production builds
+/// (`build` and `serve`, never `preview`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct BuildConfig {
+    /// Strip comments and collapse whitespace in rendered pages
+    pub minify_html: bool,
+    pub csp: CspConfig,
+}
+
+/// Where the generated Content-Security-Policy goes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CspMode {
+    #[default]
+    Off,
+    /// A `<meta http-equiv>` tag in each page's `<head>`, listing that page's inline hashes
+    Meta,
+    /// One site-wide policy in dist/_headers (Netlify / Cloudflare Pages format)
+    Headers,
+}
+
+/// `[build.csp]`: a strict policy that allows same-origin resources plus the
+/// hashes of inline scripts and styles found in rendered pages.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct CspConfig {
+    pub mode: CspMode,
+    /// Extra sources per directive, e.g. `img-src = ["https://images.example.com"]`
+    #[serde(deserialize_with = "known_directives")]
+    pub directives: BTreeMap<String, Vec<String>>,
+}
+
+/// Directives a policy may list (CSP Level 3), so a typo isn't silently ignored
+/// by browsers.
+const CSP_DIRECTIVES: [&str; 25] = [
+    "base-uri",
+    "child-src",
+    "connect-src",
+    "default-src",
+    "fenced-frame-src",
+    "font-src",
+    "form-action",
+    "frame-ancestors",
+    "frame-src",
+    "img-src",
+    "manifest-src",
+    "media-src",
+    "object-src",
+    "report-to",
+    "report-uri",
+    "require-trusted-types-for",
+    "sandbox",
+    "script-src",
+    "script-src-attr",
+    "script-src-elem",
+    "style-src",
+    "style-src-attr",
+    "style-src-elem",
+    "trusted-types",
+    "worker-src",
+];
+
+fn known_directives<'de, D>(deserializer: D) -> Result<BTreeMap<String, Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let directives = BTreeMap::<String, Vec<String>>::deserialize(deserializer)?;
+    if let Some(unknown) = directives
+        .keys()
+        .find(|name| !CSP_DIRECTIVES.contains(&name.as_str()))
+    {
+        return Err(serde::de::Error::custom(format!(
+            "unknown CSP directive '{}'",
+            unknown
+        )));
+    }
+    Ok(directives)
+}
+
+impl BuildConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PostMetadata;
+    use chrono::Days;
+
+    fn post(draft: bool, days_ahead: u64) -> Post {
+        Post {
+            metadata: PostMetadata {
+                title: "Hello".to_string(),
+                date: Local::now().date_naive() + Days::new(days_ahead),
+                description: None,
+                tags: Vec::new(),
+                draft,
+            },
+            content: String::new(),
+            html: String::new(),
+            slug: "hello".to_string(),
+            source: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_drafts_and_scheduled_posts_need_their_flag() {
+        let published = BuildOptions::default();
+        let drafts = BuildOptions {
+            drafts: true,
+            ..Default::default()
+        };
+        let future = BuildOptions {
+            future: true,
+            ..Default::default()
+        };
+
+        assert!(published.includes(&post(false, 0)));
+        assert!(!published.includes(&post(true, 0)));
+        assert!(!published.includes(&post(false, 3)));
+        assert!(drafts.includes(&post(true, 0)));
+        assert!(!drafts.includes(&post(false, 3)));
+        assert!(future.includes(&post(false, 3)));
+        assert!(!future.includes(&post(true, 0)));
+    }
+
+    #[test]
+    fn test_csp_directives_must_be_known() {
+        let config: BuildConfig =
+            toml::from_str("[csp.directives]\nimg-src = [\"https://cdn\"]\n").unwrap();
+        assert_eq!(config.csp.directives["img-src"], ["https://cdn"]);
+
+        let err = toml::from_str::<BuildConfig>("[csp.directives]\nimg-scr = [\"https://cdn\"]\n")
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown CSP directive 'img-scr'"),
+            "{err}"
+        );
+    }
+}
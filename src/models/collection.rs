@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A `[[collections]]` entry in site.toml: one page per record of a data file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollectionConfig {
+    /// Data file under `data/` (e.g. "products.csv")
+    pub data: String,
+    /// Record field used to build each page's slug
+    pub slug_field: String,
+    /// Template rendered once per record (e.g. "product.html")
+    pub template: String,
+    /// Output prefix; defaults to the data file stem
+    pub path: Option<String>,
+}
+
+impl CollectionConfig {
+    /// Name used for the data key and the `collections.<name>` listing
+    pub fn name(&self) -> String {
+        Path::new(&self.data)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.data.clone())
+    }
+
+    pub fn output_prefix(&self) -> String {
+        self.path
+            .clone()
+            .unwrap_or_else(|| self.name())
+            .trim_matches('/')
+            .to_string()
+    }
+}
+
+/// A single generated page, listable from any template via `collections.<name>`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollectionEntry {
+    pub slug: String,
+    pub template: String,
+    pub record: serde_json::Value,
+}
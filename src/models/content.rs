@@ -1,5 +1,6 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PostMetadata {
@@ -9,13 +10,29 @@ pub struct PostMetadata {
     pub description: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Drafts are only rendered in preview or with `build --drafts`
+    #[serde(default)]
+    pub draft: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Post {
     pub metadata: PostMetadata,
     pub content: String,
+    /// `content` converted to HTML (empty when only the front matter was parsed)
+    #[serde(skip)]
+    pub html: String,
     pub slug: String,
+    /// Markdown file the post was parsed from
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+impl Post {
+    /// Published with a date still in the future
+    pub fn is_scheduled(&self, today: NaiveDate) -> bool {
+        !self.metadata.draft && self.metadata.date > today
+    }
 }
 
 mod naivedate_ymd {
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    #[default]
+    Warning,
+    Error,
+}
+
+/// `[a11y]` in site.toml: the severity of each accessibility rule.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct A11yConfig {
+    /// `<img>` without an `alt` attribute (`alt=""` marks decorative images)
+    pub img_alt: Severity,
+    /// A heading more than one level deeper than the one before it
+    pub heading_order: Severity,
+    /// A link with no text, image alt or aria-label
+    pub link_text: Severity,
+    /// The same `id` used twice on one page
+    pub duplicate_id: Severity,
+    /// `<html>` without a `lang` attribute
+    pub html_lang: Severity,
+    /// A form field with no `<label>`, aria-label or title
+    pub form_label: Severity,
+}
+
+/// The accessibility rules, one per `[a11y]` key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    ImgAlt,
+    HeadingOrder,
+    LinkText,
+    DuplicateId,
+    HtmlLang,
+    FormLabel,
+}
+
+impl Rule {
+    /// The rule's key in `[a11y]`, also shown in findings
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::ImgAlt => "img-alt",
+            Rule::HeadingOrder => "heading-order",
+            Rule::LinkText => "link-text",
+            Rule::DuplicateId => "duplicate-id",
+            Rule::HtmlLang => "html-lang",
+            Rule::FormLabel => "form-label",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl A11yConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn severity(&self, rule: Rule) -> Severity {
+        match rule {
+            Rule::ImgAlt => self.img_alt,
+            Rule::HeadingOrder => self.heading_order,
+            Rule::LinkText => self.link_text,
+            Rule::DuplicateId => self.duplicate_id,
+            Rule::HtmlLang => self.html_lang,
+            Rule::FormLabel => self.form_label,
+        }
+    }
+}
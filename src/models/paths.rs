@@ -5,9 +5,13 @@ use std::{fs, path::PathBuf};
 pub struct ProjectPaths {
     pub content: PathBuf,
     pub templates: PathBuf,
+    pub data: PathBuf,
     pub dist: PathBuf,
     pub static_files: PathBuf,
+    pub themes: PathBuf,
+    pub archetypes: PathBuf,
     pub config: PathBuf,
+    pub cache: PathBuf,
 }
 
 impl Default for ProjectPaths {
@@ -15,9 +19,13 @@ impl Default for ProjectPaths {
         Self {
             content: PathBuf::from("content"),
             templates: PathBuf::from("templates"),
+            data: PathBuf::from("data"),
             dist: PathBuf::from("dist"),
             static_files: PathBuf::from("static"),
+            themes: PathBuf::from("themes"),
+            archetypes: PathBuf::from("archetypes"),
             config: PathBuf::from("site.toml"),
+            cache: PathBuf::from(".ferropress-cache"),
         }
     }
 }
@@ -28,12 +36,20 @@ impl ProjectPaths {
         Self {
             content: root.join("content"),
             templates: root.join("templates"),
+            data: root.join("data"),
             dist: root.join("dist"),
             static_files: root.join("static"),
+            themes: root.join("themes"),
+            archetypes: root.join("archetypes"),
             config: root.join("site.toml"),
+            cache: root.join(".ferropress-cache"),
         }
     }
 
+    pub fn theme_dir(&self, name: &str) -> PathBuf {
+        self.themes.join(name)
+    }
+
     pub fn dist_posts(&self) -> PathBuf {
         self.dist.join("posts")
     }
@@ -55,15 +71,6 @@ impl ProjectPaths {
         self.dist_css().join("style.css")
     }
 
-    pub fn clean_dist(&self) -> Result<()> {
-        if self.dist.exists() {
-            fs::remove_dir_all(&self.dist)
-                .with_context(|| format!("Failed to remove dist directory at {:?}", self.dist))?;
-            println!("🧹 Dist directory cleared.");
-        }
-        Ok(())
-    }
-
     pub fn create_dist_folders(&self) -> Result<()> {
         fs::create_dir_all(&self.dist)
             .with_context(|| format!("Failed to create dist directory at {:?}", self.dist))?;
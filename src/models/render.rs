@@ -1,7 +1,11 @@
 use serde::Serialize;
+use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct RenderedPage {
     pub slug: String,
     pub html: String,
+    /// File the page came from: the Markdown post, or the template for pages
+    /// and collection entries
+    pub source: PathBuf,
 }
@@ -1,23 +1,51 @@
+use crate::models::{A11yConfig, BuildConfig, CollectionConfig, CollectionEntry};
 use anyhow::{Context, Result};
 use chrono::{Datelike, Local};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SiteConfig {
     pub title: String,
+    /// Public root of the site, e.g. "https://example.com/blog/" (defaults to "/")
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    /// Language of the content, used for `<html lang>` (defaults to "en")
+    #[serde(default = "default_language")]
+    pub language: String,
     pub author: String,
     pub footer_text: String,
+    /// Theme under `themes/<name>/` providing fallback templates and static files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
     #[serde(default)]
     pub navbar_links: Vec<NavbarLink>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collections: Vec<CollectionConfig>,
+    /// Severity of each accessibility rule (`check --a11y`, `build --a11y`)
+    #[serde(default, skip_serializing_if = "A11yConfig::is_default")]
+    pub a11y: A11yConfig,
+    /// Production-only output settings such as HTML minification
+    #[serde(default, skip_serializing_if = "BuildConfig::is_default")]
+    pub build: BuildConfig,
+    /// Global data loaded from `data/`, keyed by file stem (not part of site.toml)
+    #[serde(skip)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+    /// Pages generated from `collections`, keyed by collection name
+    #[serde(skip)]
+    pub collection_entries: BTreeMap<String, Vec<CollectionEntry>>,
 }
 
 impl Default for SiteConfig {
     fn default() -> Self {
         Self {
             title: "My New Forge".to_string(),
+            base_url: default_base_url(),
+            language: default_language(),
             author: "Ironmaster".to_string(),
             footer_text: "Forged with FerroPress".to_string(),
+            theme: None,
             navbar_links: vec![
                 NavbarLink {
                     label: "Home".into(),
@@ -28,30 +56,79 @@ impl Default for SiteConfig {
                     url: "/posts".into(),
                 },
             ],
+            collections: Vec::new(),
+            a11y: A11yConfig::default(),
+            build: BuildConfig::default(),
+            data: serde_json::Map::new(),
+            collection_entries: BTreeMap::new(),
         }
     }
 }
 
 impl SiteConfig {
-    pub fn load(path: &Path) -> Result<Self> {
+    /// Read site.toml as a raw table, before any theme defaults are merged in.
+    pub fn load_table(path: &Path) -> Result<toml::Table> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Could not read config at {:?}. Did you run 'init'?", path))?;
 
-        let config: Self = toml::from_str(&content)
-            .with_context(|| "Failed to parse site.toml (invalid TOML)".to_string())?;
+        toml::from_str(&content)
+            .with_context(|| "Failed to parse site.toml (invalid TOML)".to_string())
+    }
 
+    pub fn from_table(table: toml::Table) -> Result<Self> {
+        let config: Self = toml::Value::Table(table)
+            .try_into()
+            .context("Invalid site.toml settings")?;
         Ok(config)
     }
 
     pub fn base_context(&self) -> tera::Context {
         let mut ctx = tera::Context::new();
         ctx.insert("site", self);
+        ctx.insert("data", &self.data);
+        ctx.insert("collections", &self.collection_entries);
 
         let current_year: i32 = Local::now().year();
         ctx.insert("current_year", &current_year);
 
         ctx
     }
+
+    /// Build a URL for a site path, honoring any path prefix in `base_url`.
+    /// Absolute URLs need a `base_url` with a scheme and host; otherwise a root-relative URL is returned.
+    pub fn url_for(&self, path: &str, absolute: bool) -> String {
+        let base = self.base_url.trim();
+        let (origin, prefix) = match base.find("://") {
+            Some(i) => {
+                let after_scheme = &base[i + 3..];
+                let split = after_scheme.find('/').map_or(base.len(), |j| i + 3 + j);
+                (&base[..split], &base[split..])
+            }
+            None => ("", base),
+        };
+
+        let prefix = prefix.trim_matches('/');
+        let path = path.trim_start_matches('/');
+        let relative = match (prefix.is_empty(), path.is_empty()) {
+            (true, _) => format!("/{}", path),
+            (false, true) => format!("/{}/", prefix),
+            (false, false) => format!("/{}/{}", prefix, path),
+        };
+
+        if absolute && !origin.is_empty() {
+            format!("{}{}", origin, relative)
+        } else {
+            relative
+        }
+    }
+}
+
+fn default_base_url() -> String {
+    "/".to_string()
+}
+
+fn default_language() -> String {
+    "en".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -59,3 +136,23 @@ pub struct NavbarLink {
     pub label: String,
     pub url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_for_respects_base_url() {
+        let mut config = SiteConfig::default();
+        assert_eq!(config.url_for("posts/hello", false), "/posts/hello");
+        assert_eq!(config.url_for("posts/hello", true), "/posts/hello");
+
+        config.base_url = "https://example.com/blog/".into();
+        assert_eq!(config.url_for("/posts/hello", false), "/blog/posts/hello");
+        assert_eq!(
+            config.url_for("posts/hello", true),
+            "https://example.com/blog/posts/hello"
+        );
+        assert_eq!(config.url_for("", true), "https://example.com/blog/");
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// `themes/<name>/theme.toml`: theme metadata plus default site.toml values.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ThemeManifest {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Defaults merged underneath the project's site.toml
+    #[serde(default)]
+    pub config: toml::Table,
+}
+
+impl ThemeManifest {
+    /// Load `theme.toml` from a theme directory. A missing manifest yields empty defaults.
+    pub fn load(theme_dir: &Path) -> Result<Self> {
+        let path = theme_dir.join("theme.toml");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read theme manifest {:?}", path))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse theme manifest {:?}", path))
+    }
+}